@@ -9,15 +9,24 @@ Help()
    echo "Modify the keyboard events and run the Pong client"
    echo "in a docker container"
    echo "      "
-   echo "Syntax: ./launch.sh LOCATION [R_DELAY] [R_RECURSIVE]"
+   echo "Syntax: ./launch.sh [-r] LOCATION [R_DELAY] [R_RECURSIVE]"
+   echo "        ./launch.sh history [FILTERS]"
+   echo "        ./launch.sh export [STARTED_AT] [--size COLSxROWS] [--output FILE]"
    echo "      "
    echo "R_DELAY is to custom delay before first KeyRepeat Event" 
    echo "(default 660 ms)"
    echo "R_RECURSIVE is to custom delay between KeyRepeat Events"
    echo "(default 25 ms)"
+   echo "history prints the matches recorded on this computer,"
+   echo "FILTERS: --opponent NAME --server LOCATION --since YYYY-MM-DD"
+   echo "         --limit N --won --lost"
+   echo "export converts a recorded match (default: the last one)"
+   echo "to an asciicast file in $HOME/.local/share/pong-cli/casts,"
+   echo "named STARTED_AT.cast unless --output gives another name"
    echo "      "
    echo "options:"
    echo "-h     print this help."
+   echo "-r     record the session as an asciicast file."
    echo
 }
 
@@ -27,17 +36,40 @@ Help()
 ############################################################
 ############################################################
 
-while getopts ":h" option; do
+record=""
+
+while getopts ":hr" option; do
    case $option in
       h) # display Help
          Help
          exit;;
+      r) # record the session
+         record="--record";;
      \?) # Invalid option
          echo "Error: Invalid option"
          exit;;
    esac
 done
 
+shift $((OPTIND - 1))
+
+data_dir="$HOME/.local/share/pong-cli"
+config_dir="$HOME/.config/pong-cli"
+mkdir -p "$data_dir" "$config_dir"
+
+# Keep the history and the theme file on this computer, and pass the display settings through
+docker_options=(
+    -v "$data_dir:/app/data" -e PONG_DATA_DIR=/app/data
+    -v "$config_dir:/root/.config/pong-cli"
+    -e PONG_THEME -e NO_COLOR -e PONG_MARKER -e PONG_MOUSE
+)
+
+if [ "$1" = "history" ] || [ "$1" = "export" ]
+then
+    docker run --rm --name cli-pong -it "${docker_options[@]}" cli:latest ./cli_app "$@"
+    exit
+fi
+
 r_delay=660
 r_rate=25
 
@@ -53,6 +85,6 @@ if [[ ! "$r_delay" =~ ^[0-9]+$ || ! "$r_rate" =~ ^[0-9]+$ ]]; then
 fi
 
 xset r rate 150 30
-docker run --rm --name cli-pong -it cli:latest ./cli_app $1
+docker run --rm --name cli-pong -it "${docker_options[@]}" cli:latest ./cli_app $1 $record
 xset r rate $r_delay $r_rate
 echo "Thank you for playing"
\ No newline at end of file
@@ -0,0 +1,134 @@
+use crate::game::GameStats;
+use std::time::Instant;
+
+///Paddle hits, counted from the changes of direction of the ball
+#[derive(Default)]
+pub(crate) struct Rallies {
+    ///Hits over the whole game
+    pub(crate) hits: u32,
+    ///Hits of the longest point
+    pub(crate) longest: u32,
+    ///Hits of each finished point
+    pub(crate) per_point: Vec<u64>,
+    current: u32,
+    ///Whether the ball was last moving right, None after a point
+    rightward: Option<bool>,
+}
+
+impl Rallies {
+    ///Count a hit when the ball goes back the way it came
+    fn ball_moved(&mut self, rightward: bool) {
+        if self
+            .rightward
+            .is_some_and(|was_rightward| was_rightward != rightward)
+        {
+            self.hits += 1;
+            self.current += 1;
+            self.longest = self.longest.max(self.current);
+        }
+        self.rightward = Some(rightward);
+    }
+    ///Close the current point
+    fn scored(&mut self) {
+        self.per_point.push(self.current as u64);
+        self.current = 0;
+        self.rightward = None;
+    }
+}
+
+///Statistics of a match, gathered from the frames sent by the server
+///
+/// The left player is the player, the right one the opponent, as on the score display
+#[derive(Default)]
+pub(crate) struct MatchAnalytics {
+    pub(crate) rallies: Rallies,
+    ///Seconds since the first frame at which each point was scored, and whether the left
+    /// player won it
+    pub(crate) points: Vec<(f32, bool)>,
+    ///Seconds each paddle spent moving, left then right
+    pub(crate) moving: (f32, f32),
+    ///Distance travelled by the ball while in play, in field units
+    ball_distance: f32,
+    ///Time the ball spent in play, in seconds
+    ball_time: f32,
+    start: Option<Instant>,
+    previous: Option<(GameStats, Instant)>,
+}
+
+impl MatchAnalytics {
+    ///Update the statistics with a new frame
+    pub(crate) fn update(&mut self, stats: &GameStats) {
+        let now = Instant::now();
+        let start = *self.start.get_or_insert(now);
+        let Some((previous, received)) = self.previous.replace((*stats, now)) else {
+            return;
+        };
+        let elapsed = now.duration_since(received).as_secs_f32();
+        if stats.left_y != previous.left_y {
+            self.moving.0 += elapsed;
+        }
+        if stats.right_y != previous.right_y {
+            self.moving.1 += elapsed;
+        }
+        if stats.player1_score != previous.player1_score
+            || stats.player2_score != previous.player2_score
+        {
+            let at = now.duration_since(start).as_secs_f32();
+            self.points
+                .push((at, stats.player1_score > previous.player1_score));
+            self.rallies.scored();
+            return;
+        }
+        let (dx, dy) = (stats.ball_x - previous.ball_x, stats.ball_y - previous.ball_y);
+        if dx == 0.0 {
+            return;
+        }
+        self.ball_distance += (dx * dx + dy * dy).sqrt();
+        self.ball_time += elapsed;
+        self.rallies.ball_moved(dx > 0.0);
+    }
+    ///Average speed of the ball in play, in field units per second
+    pub(crate) fn average_ball_speed(&self) -> f32 {
+        match self.ball_time > 0.0 {
+            true => self.ball_distance / self.ball_time,
+            false => 0.0,
+        }
+    }
+    ///Points won by the left and right players during each minute of the match
+    pub(crate) fn points_per_minute(&self) -> Vec<(u64, u64)> {
+        let minutes = self
+            .points
+            .last()
+            .map(|(at, _)| *at as usize / 60 + 1)
+            .unwrap_or_default();
+        let mut buckets = vec![(0, 0); minutes];
+        for (at, left) in &self.points {
+            let bucket = &mut buckets[*at as usize / 60];
+            match left {
+                true => bucket.0 += 1,
+                false => bucket.1 += 1,
+            }
+        }
+        buckets
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hits_are_counted_per_point() {
+        let mut rallies = Rallies::default();
+        for rightward in [true, false, true, true, false] {
+            rallies.ball_moved(rightward);
+        }
+        rallies.scored();
+        rallies.ball_moved(false);
+        rallies.ball_moved(true);
+        rallies.scored();
+        assert_eq!(rallies.hits, 4);
+        assert_eq!(rallies.longest, 3);
+        assert_eq!(rallies.per_point, vec![3, 1]);
+    }
+}
@@ -0,0 +1,237 @@
+use crate::history::{data_dir, load_history};
+use crate::replay::ReplayPlayer;
+use crate::screen_displays::print_replay;
+use crate::utils::now_timestamp;
+use anyhow::{Result, anyhow};
+use ratatui::buffer::Buffer;
+use ratatui::layout::Rect;
+use ratatui::style::{Color, Modifier};
+use std::fmt::Write as _;
+use std::fs::{self, File};
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+
+///Terminal size used when exporting a replay, unless --size is given
+const EXPORT_SIZE: (u16, u16) = (100, 30);
+
+///Writer of an asciicast v2 recording
+///
+/// Every captured buffer is stored as the escape sequences turning the previous one into it
+pub(crate) struct CastWriter {
+    file: BufWriter<File>,
+    start: Instant,
+    previous: Option<Buffer>,
+}
+
+impl CastWriter {
+    ///Create the recording and write its header
+    ///
+    /// #Parameters
+    /// - width, height: size of the recorded terminal
+    pub(crate) fn create(path: &Path, width: u16, height: u16, title: &str) -> Result<CastWriter> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let mut file = BufWriter::new(File::create(path)?);
+        let header = serde_json::json!({
+            "version": 2,
+            "width": width,
+            "height": height,
+            "timestamp": now_timestamp(),
+            "title": title,
+        });
+        writeln!(file, "{header}")?;
+        Ok(CastWriter {
+            file,
+            start: Instant::now(),
+            previous: None,
+        })
+    }
+    ///Record a buffer drawn by the running client
+    pub(crate) fn capture(&mut self, buffer: &Buffer) -> Result<()> {
+        self.write_frame(self.start.elapsed().as_secs_f64(), buffer)
+    }
+    ///Record a buffer displayed at the given time
+    ///
+    /// #Parameters
+    /// - time: seconds elapsed since the beginning of the recording
+    pub(crate) fn write_frame(&mut self, time: f64, buffer: &Buffer) -> Result<()> {
+        let output = match &self.previous {
+            Some(previous) if previous.area == buffer.area => ansi_diff(previous, buffer),
+            _ => {
+                let blank = Buffer::empty(buffer.area);
+                format!("\x1b[?25l\x1b[0m\x1b[2J{}", ansi_diff(&blank, buffer))
+            }
+        };
+        if !output.is_empty() {
+            writeln!(self.file, "{}", serde_json::json!([time, "o", output]))?;
+        }
+        self.previous = Some(buffer.clone());
+        Ok(())
+    }
+    pub(crate) fn finish(mut self) -> Result<()> {
+        self.file.flush()?;
+        Ok(())
+    }
+}
+
+///Escape sequences drawing the cells of `next` which differ from `previous`
+fn ansi_diff(previous: &Buffer, next: &Buffer) -> String {
+    let mut output = String::new();
+    let mut cursor: Option<(u16, u16)> = None;
+    let mut style = None;
+    for (x, y, cell) in previous.diff(next) {
+        if cursor != Some((x, y)) {
+            let _ = write!(output, "\x1b[{};{}H", y + 1, x + 1);
+        }
+        let cell_style = (cell.fg, cell.bg, cell.modifier);
+        if style != Some(cell_style) {
+            output.push_str(&sgr(cell.fg, cell.bg, cell.modifier));
+            style = Some(cell_style);
+        }
+        output.push_str(cell.symbol());
+        cursor = Some((x + 1, y));
+    }
+    if style.is_some() {
+        output.push_str("\x1b[0m");
+    }
+    output
+}
+
+///Select Graphic Rendition sequence of a cell's style
+fn sgr(fg: Color, bg: Color, modifier: Modifier) -> String {
+    let mut codes = vec!["0".to_string()];
+    for (flag, code) in [
+        (Modifier::BOLD, "1"),
+        (Modifier::DIM, "2"),
+        (Modifier::ITALIC, "3"),
+        (Modifier::UNDERLINED, "4"),
+        (Modifier::SLOW_BLINK, "5"),
+        (Modifier::REVERSED, "7"),
+        (Modifier::HIDDEN, "8"),
+        (Modifier::CROSSED_OUT, "9"),
+    ] {
+        if modifier.contains(flag) {
+            codes.push(code.to_string());
+        }
+    }
+    if let Some(code) = color_code(fg, false) {
+        codes.push(code);
+    }
+    if let Some(code) = color_code(bg, true) {
+        codes.push(code);
+    }
+    format!("\x1b[{}m", codes.join(";"))
+}
+
+fn color_code(color: Color, background: bool) -> Option<String> {
+    let offset = if background { 10 } else { 0 };
+    let code = match color {
+        Color::Reset => return None,
+        Color::Black => 30,
+        Color::Red => 31,
+        Color::Green => 32,
+        Color::Yellow => 33,
+        Color::Blue => 34,
+        Color::Magenta => 35,
+        Color::Cyan => 36,
+        Color::Gray => 37,
+        Color::DarkGray => 90,
+        Color::LightRed => 91,
+        Color::LightGreen => 92,
+        Color::LightYellow => 93,
+        Color::LightBlue => 94,
+        Color::LightMagenta => 95,
+        Color::LightCyan => 96,
+        Color::White => 97,
+        Color::Indexed(index) => return Some(format!("{};5;{index}", 38 + offset)),
+        Color::Rgb(r, g, b) => return Some(format!("{};2;{r};{g};{b}", 38 + offset)),
+    };
+    Some((code + offset).to_string())
+}
+
+///Directory holding the exported recordings
+fn casts_dir() -> Result<PathBuf> {
+    Ok(data_dir()?.join("casts"))
+}
+
+///Path of the live session recording asked on the command line, if any
+///
+/// `--record` may be followed by a file, relative ones being written to the casts directory
+/// which is shared with the container like the exported recordings
+pub(crate) fn session_path(mut args: impl Iterator<Item = String>) -> Result<Option<PathBuf>> {
+    while let Some(arg) = args.next() {
+        if arg == "--record" {
+            return match args.next() {
+                //Joining keeps absolute paths as they are
+                Some(path) => Ok(Some(casts_dir()?.join(path))),
+                _ => Ok(Some(
+                    casts_dir()?.join(format!("session-{}.cast", now_timestamp())),
+                )),
+            };
+        }
+    }
+    Ok(None)
+}
+
+///Parse a terminal size written as COLSxROWS
+fn parse_size(size: &str) -> Result<(u16, u16)> {
+    match size.split_once('x') {
+        Some((width, height)) => Ok((width.parse()?, height.parse()?)),
+        _ => Err(anyhow!("invalid size {size}, expected COLSxROWS")),
+    }
+}
+
+///Run the export subcommand: convert a recorded match to an asciicast file
+///
+/// Exports the most recent match unless the start timestamp of another one is given.
+/// A relative `--output` is written under the casts directory, the only one shared
+/// with the Docker container
+///
+/// #Errors
+/// Returns an error on unknown options or if the match has no replay
+pub(crate) fn export_replay(mut args: impl Iterator<Item = String>) -> Result<()> {
+    let mut started_at = None;
+    let mut output = None;
+    let mut size = EXPORT_SIZE;
+    while let Some(arg) = args.next() {
+        let mut value = |option: &str| match args.next() {
+            Some(value) => Ok(value),
+            _ => Err(anyhow!("missing value for {option}")),
+        };
+        match arg.as_str() {
+            "--output" => output = Some(PathBuf::from(value(&arg)?)),
+            "--size" => size = parse_size(&value(&arg)?)?,
+            _ if !arg.starts_with("--") => started_at = Some(arg.parse::<u64>()?),
+            _ => return Err(anyhow!("unknown option {arg}")),
+        }
+    }
+    let history = load_history()?;
+    let record = match started_at {
+        Some(started_at) => history.iter().find(|record| record.started_at == started_at),
+        _ => history.first(),
+    };
+    let Some(record) = record else {
+        return Err(anyhow!("No match recorded"));
+    };
+    let started_at = record.started_at;
+    let output = match output {
+        //Joining keeps absolute paths as they are
+        Some(output) => casts_dir()?.join(output),
+        _ => casts_dir()?.join(format!("{started_at}.cast")),
+    };
+    let mut replay = ReplayPlayer::load(started_at, &record.opponent)?;
+    let title = format!("Pong against {}", replay.opponent);
+    let mut writer = CastWriter::create(&output, size.0, size.1, &title)?;
+    let area = Rect::new(0, 0, size.0, size.1);
+    for index in 0..replay.frames.len() {
+        replay.show_frame(index);
+        let mut buffer = Buffer::empty(area);
+        print_replay(&replay, area, &mut buffer);
+        writer.write_frame(replay.position / 1000.0, &buffer)?;
+    }
+    writer.finish()?;
+    println!("Exported to {}", output.display());
+    Ok(())
+}
@@ -1,8 +1,11 @@
+use crate::directory::UserDirectory;
 use reqwest::Client;
+use std::cell::RefCell;
 
 pub(crate) struct Context {
     pub(crate) location: String,
     pub(crate) client: Client,
+    pub(crate) users: RefCell<UserDirectory>,
 }
 
 
@@ -18,6 +21,7 @@ impl Context {
                 .danger_accept_invalid_certs(true)
                 .build()
                 .expect("Impossible to build new client, try again"),
+            users: RefCell::new(UserDirectory::default()),
         }
     }
 }
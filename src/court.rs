@@ -0,0 +1,133 @@
+use ratatui::{layout::Rect, symbols::Marker};
+use std::sync::OnceLock;
+
+///Height of a terminal cell over its width
+const CELL_RATIO: f64 = 2.0;
+///Smallest area, borders included, the court is drawn in
+pub(crate) const MIN_WIDTH: u16 = 42;
+pub(crate) const MIN_HEIGHT: u16 = 12;
+
+///Geometry of the court, in the server's coordinates: 0 to 100 on both axes, y going down
+#[derive(Clone, Copy, PartialEq)]
+pub(crate) struct CourtConfig {
+    ///Width of the court over its height
+    pub(crate) ratio: f64,
+    pub(crate) paddle_width: f64,
+    pub(crate) paddle_height: f64,
+    ///Distance between the left wall and the back of the left paddle
+    pub(crate) left_offset: f64,
+    ///Distance between the right wall and the back of the right paddle
+    pub(crate) right_offset: f64,
+    pub(crate) ball_radius: f64,
+}
+
+impl Default for CourtConfig {
+    fn default() -> Self {
+        CourtConfig {
+            ratio: 4.0 / 3.0,
+            paddle_width: 2.0,
+            paddle_height: 10.0,
+            //Paddles at 1.5 and 97.0, where the court was always drawn
+            left_offset: 1.5,
+            right_offset: 1.0,
+            ball_radius: 0.5,
+        }
+    }
+}
+
+impl CourtConfig {
+    ///Read the configuration sent by the server at match start, defaults filling missing fields
+    pub(crate) fn from_value(value: &serde_json::Value) -> CourtConfig {
+        let default = CourtConfig::default();
+        let field = |key: &str, default: f64| {
+            value[key]
+                .as_f64()
+                .filter(|field| field.is_finite() && *field > 0.0)
+                .unwrap_or(default)
+        };
+        let ratio = match (value["width"].as_f64(), value["height"].as_f64()) {
+            (Some(width), Some(height)) if width > 0.0 && height > 0.0 => width / height,
+            _ => field("ratio", default.ratio),
+        };
+        //A single offset sent by the server applies to both sides
+        let offset = |key: &str, default: f64| field(key, field("paddleOffset", default));
+        CourtConfig {
+            ratio,
+            paddle_width: field("paddleWidth", default.paddle_width),
+            paddle_height: field("paddleHeight", default.paddle_height),
+            left_offset: offset("leftPaddleOffset", default.left_offset),
+            right_offset: offset("rightPaddleOffset", default.right_offset),
+            ball_radius: field("ballRadius", default.ball_radius),
+        }
+    }
+    ///Left edge of the left paddle
+    pub(crate) fn left_paddle_x(&self) -> f64 {
+        self.left_offset
+    }
+    ///Left edge of the right paddle
+    pub(crate) fn right_paddle_x(&self) -> f64 {
+        100.0 - self.right_offset - self.paddle_width
+    }
+}
+
+///Marker used to draw the court
+///
+/// Braille by default. PONG_MARKER chooses among braille, halfblock, block and dot,
+/// half blocks being used on terminals unlikely to have a Braille font
+pub(crate) fn marker() -> Marker {
+    static MARKER: OnceLock<Marker> = OnceLock::new();
+    *MARKER.get_or_init(|| {
+        let chosen = std::env::var("PONG_MARKER").unwrap_or_default();
+        match chosen.to_lowercase().as_str() {
+            "braille" => Marker::Braille,
+            "halfblock" | "half-block" => Marker::HalfBlock,
+            "block" => Marker::Block,
+            "dot" => Marker::Dot,
+            _ if braille_supported() => Marker::Braille,
+            _ => Marker::HalfBlock,
+        }
+    })
+}
+
+///Whether the terminal can be expected to display Braille characters
+fn braille_supported() -> bool {
+    if std::env::var("TERM").is_ok_and(|term| term == "linux") {
+        return false;
+    }
+    let locale = ["LC_ALL", "LC_CTYPE", "LANG"]
+        .iter()
+        .find_map(|name| std::env::var(name).ok().filter(|value| !value.is_empty()));
+    match locale {
+        Some(locale) => {
+            let locale = locale.to_lowercase();
+            locale.contains("utf-8") || locale.contains("utf8")
+        }
+        _ => true,
+    }
+}
+
+///Whether the area is large enough to draw the court in
+pub(crate) fn fits(area: Rect) -> bool {
+    area.width >= MIN_WIDTH && area.height >= MIN_HEIGHT
+}
+
+///Largest area inside the given one keeping the proportions of the court, centered
+///
+/// The area includes the borders drawn around the court
+pub(crate) fn letterbox(area: Rect, config: &CourtConfig) -> Rect {
+    let inner_width = area.width.saturating_sub(2) as f64;
+    let inner_height = area.height.saturating_sub(2) as f64;
+    let cells_ratio = config.ratio * CELL_RATIO;
+    let (width, height) = match inner_height * cells_ratio <= inner_width {
+        true => (inner_height * cells_ratio, inner_height),
+        false => (inner_width, inner_width / cells_ratio),
+    };
+    let width = (width.round() as u16 + 2).min(area.width);
+    let height = (height.round() as u16 + 2).min(area.height);
+    Rect {
+        x: area.x + (area.width - width) / 2,
+        y: area.y + (area.height - height) / 2,
+        width,
+        height,
+    }
+}
@@ -0,0 +1,213 @@
+use crate::Context;
+use crate::friends::Presence;
+use crate::profile::Profile;
+use anyhow::{Result, anyhow};
+use futures::future::join_all;
+use std::collections::HashMap;
+use std::rc::Rc;
+use std::time::{Duration, Instant};
+
+///Cached profiles are dropped after this delay
+const USER_TTL: Duration = Duration::from_secs(60);
+///Cached profiles older than this are refreshed when the client is idle
+const USER_REFRESH: Duration = Duration::from_secs(30);
+///Maximum number of profiles refreshed per idle tick
+const REFRESH_BATCH: usize = 8;
+///Maximum number of users suggested by a search
+const SEARCH_RESULTS: usize = 8;
+
+struct CachedUser {
+    profile: Profile,
+    fetched: Instant,
+}
+
+///Users already fetched from the server, shared through the Context
+///
+/// Avoids requesting the same profile each time a screen is drawn
+#[derive(Default)]
+pub(crate) struct UserDirectory {
+    users: HashMap<u64, CachedUser>,
+    ids: HashMap<String, u64>,
+}
+
+impl UserDirectory {
+    fn get(&self, id: u64) -> Option<Profile> {
+        match self.users.get(&id) {
+            Some(user) if user.fetched.elapsed() < USER_TTL => Some(user.profile.clone()),
+            _ => None,
+        }
+    }
+    fn get_id(&self, name: &str) -> Option<u64> {
+        let id = self.ids.get(name)?;
+        self.get(*id)
+            .filter(|profile| profile.name == name)
+            .map(|profile| profile.id)
+    }
+    fn insert(&mut self, profile: Profile) {
+        //Forget the previous name of a renamed user
+        if let Some(user) = self.users.get(&profile.id)
+            && user.profile.name != profile.name
+            && self.ids.get(&user.profile.name) == Some(&profile.id)
+        {
+            self.ids.remove(&user.profile.name);
+        }
+        self.ids.insert(profile.name.clone(), profile.id);
+        self.users.insert(
+            profile.id,
+            CachedUser {
+                profile,
+                fetched: Instant::now(),
+            },
+        );
+    }
+    ///Apply a status change received from the chat to a cached user
+    pub(crate) fn set_presence(&mut self, id: u64, presence: Presence) {
+        if let Some(user) = self.users.get_mut(&id) {
+            user.profile.presence = presence;
+        }
+    }
+    pub(crate) fn invalidate(&mut self, id: u64) {
+        if let Some(user) = self.users.remove(&id) {
+            self.ids.remove(&user.profile.name);
+        }
+    }
+    fn search(&self, query: &str) -> Vec<Profile> {
+        let query = query.to_lowercase();
+        let mut result: Vec<Profile> = self
+            .users
+            .values()
+            .filter(|user| user.fetched.elapsed() < USER_TTL)
+            .filter(|user| user.profile.name.to_lowercase().contains(&query))
+            .map(|user| user.profile.clone())
+            .collect();
+        result.sort_by_key(|profile| {
+            (
+                !profile.name.to_lowercase().starts_with(&query),
+                profile.name.to_lowercase(),
+            )
+        });
+        result
+    }
+    fn stale_ids(&self) -> Vec<u64> {
+        let mut stale: Vec<(&u64, &CachedUser)> = self
+            .users
+            .iter()
+            .filter(|(_, user)| user.fetched.elapsed() >= USER_REFRESH)
+            .collect();
+        stale.sort_by_key(|(_, user)| user.fetched);
+        stale
+            .into_iter()
+            .map(|(id, _)| *id)
+            .take(REFRESH_BATCH)
+            .collect()
+    }
+}
+
+///Get a user's profile, from the cache when possible
+pub(crate) async fn get_user(context: Rc<Context>, id: u64) -> Result<Profile> {
+    if let Some(profile) = context.users.borrow().get(id) {
+        return Ok(profile);
+    }
+    let profile = Profile::fetch(context.clone(), id).await?;
+    context.users.borrow_mut().insert(profile.clone());
+    Ok(profile)
+}
+
+///Get several profiles at once
+///
+/// Missing profiles are requested concurrently
+///
+/// #Return
+/// The profiles found, users which could not be fetched being left out
+pub(crate) async fn get_users(context: Rc<Context>, ids: &[u64]) -> HashMap<u64, Profile> {
+    let mut result: HashMap<u64, Profile> = HashMap::new();
+    let mut missing: Vec<u64> = vec![];
+    for id in ids {
+        match context.users.borrow().get(*id) {
+            Some(profile) => {
+                result.insert(*id, profile);
+            }
+            _ => missing.push(*id),
+        }
+    }
+    missing.sort_unstable();
+    missing.dedup();
+    let fetched = join_all(
+        missing
+            .iter()
+            .map(|id| Profile::fetch(context.clone(), *id)),
+    )
+    .await;
+    for profile in fetched.into_iter().flatten() {
+        context.users.borrow_mut().insert(profile.clone());
+        result.insert(profile.id, profile);
+    }
+    result
+}
+
+///Get a user's id from his exact name, from the cache when possible
+pub(crate) async fn get_id(context: Rc<Context>, name: &str) -> Result<u64> {
+    if let Some(id) = context.users.borrow().get_id(name) {
+        return Ok(id);
+    }
+    let apiloc = format!(
+        "https://{}/api/user/get_profile_name?profile_name={}",
+        context.location, name
+    );
+    let response = context.client.get(apiloc).send().await?;
+    let response: serde_json::Value = response.json().await?;
+    let id = match response["id"].as_u64() {
+        Some(id) => id,
+        _ => return Err(anyhow!("Friend not found")),
+    };
+    if let Some(profile) = Profile::from_value(&response, id) {
+        context.users.borrow_mut().insert(profile);
+    }
+    Ok(id)
+}
+
+///Users whose name contains `query`, cached ones first
+///
+/// Server errors are ignored so that cached results are still suggested
+pub(crate) async fn search_users(context: Rc<Context>, query: &str) -> Result<Vec<Profile>> {
+    let mut result = context.users.borrow().search(query);
+    let apiloc = format!("https://{}/api/user/search", context.location);
+    let response = context
+        .client
+        .get(apiloc)
+        .query(&[("name", query)])
+        .send()
+        .await;
+    if let Ok(response) = response
+        && response.status().is_success()
+        && let Ok(serde_json::Value::Array(users)) = response.json().await
+    {
+        //Results without an id can not be opened nor sent a request
+        let profiles = users
+            .iter()
+            .filter_map(|user| Profile::from_value(user, user["id"].as_u64()?));
+        for profile in profiles {
+            if !result.iter().any(|known| known.id == profile.id) {
+                result.push(profile);
+            }
+        }
+    }
+    result.truncate(SEARCH_RESULTS);
+    Ok(result)
+}
+
+///Refresh the oldest cached profiles before they expire
+///
+/// Called while the client waits for user input, so that drawing a screen
+/// does not have to wait for the server
+pub(crate) async fn refresh_stale(context: Rc<Context>) -> Result<()> {
+    let stale = context.users.borrow().stale_ids();
+    let fetched = join_all(stale.iter().map(|id| Profile::fetch(context.clone(), *id))).await;
+    for (id, profile) in stale.into_iter().zip(fetched) {
+        match profile {
+            Ok(profile) => context.users.borrow_mut().insert(profile),
+            Err(_) => context.users.borrow_mut().invalidate(id),
+        }
+    }
+    Ok(())
+}
@@ -1,25 +1,120 @@
 use crate::Auth;
 use crate::Context;
-use crate::CurrentScreen;
-use crate::utils::{get_name_from_id, get_id_from_name};
+use crate::directory::{get_id, get_users, search_users};
+use crate::keymap::{self, Action, Help};
+use crate::pager::Pager;
+use crate::profile::Profile;
+use crate::router::Router;
+use crate::theme::theme;
 use crate::utils::should_exit;
 use anyhow::{Result, anyhow};
 use crossterm::event::poll;
-use crossterm::event::{self, Event, KeyCode};
-use std::cell::{Cell, RefCell};
+use crossterm::event::{Event, KeyCode};
+use ratatui::style::Color;
+use reqwest::Method;
+use std::cell::RefCell;
 use std::collections::HashMap;
 use std::rc::Rc;
-use std::time::Duration;
+use std::time::{Duration, Instant};
+
+///Friends list is requested again after this delay
+const FRIENDS_REFRESH: Duration = Duration::from_secs(5);
+///Delay without typing before users are searched
+const SEARCH_DEBOUNCE: Duration = Duration::from_millis(300);
+
+///Relationship between us and a user of our friends list
+#[derive(Clone, Copy, PartialEq, Default)]
+pub(crate) enum FriendStatus {
+    #[default]
+    Accepted,
+    ///We sent the request, waiting for the other user to answer
+    Outgoing,
+    ///The other user sent us a request
+    Incoming,
+}
+
+///Availability of a user, as reported by the server
+#[derive(Clone, Copy, PartialEq, Default)]
+pub(crate) enum Presence {
+    Online,
+    InQueue,
+    InGame,
+    #[default]
+    Offline,
+}
+
+impl Presence {
+    ///Parse a status sent by the server, as in `{"type": "status", "userId": 4, "status": "online"}`
+    /// chat messages
+    pub(crate) fn from_value(value: &serde_json::Value) -> Option<Presence> {
+        match value.as_str()?.to_lowercase().as_str() {
+            "online" => Some(Presence::Online),
+            "queue" | "in_queue" | "inqueue" => Some(Presence::InQueue),
+            "game" | "in_game" | "ingame" | "playing" => Some(Presence::InGame),
+            "offline" => Some(Presence::Offline),
+            _ => None,
+        }
+    }
+    pub(crate) fn label(&self) -> &'static str {
+        match self {
+            Presence::Online => "online",
+            Presence::InQueue => "in queue",
+            Presence::InGame => "in game",
+            Presence::Offline => "offline",
+        }
+    }
+    pub(crate) fn color(&self) -> Color {
+        match self {
+            Presence::Online => theme().positive,
+            Presence::InQueue => theme().highlight,
+            Presence::InGame => theme().special,
+            Presence::Offline => theme().muted,
+        }
+    }
+    ///Available players come first when sorting
+    fn rank(&self) -> u8 {
+        match self {
+            Presence::Online => 0,
+            Presence::InQueue => 1,
+            Presence::InGame => 2,
+            Presence::Offline => 3,
+        }
+    }
+}
+
+#[derive(Clone, Default)]
+pub(crate) struct Friend {
+    pub(crate) id: u64,
+    pub(crate) name: String,
+    pub(crate) status: FriendStatus,
+    pub(crate) presence: Presence,
+}
+
+impl Friend {
+    ///Name as displayed in friends lists
+    pub(crate) fn label(&self) -> String {
+        match self.status {
+            FriendStatus::Accepted => self.name.clone(),
+            FriendStatus::Outgoing => format!("{} (Pending)", self.name),
+            FriendStatus::Incoming => format!("{} (Wants to be your friend)", self.name),
+        }
+    }
+}
 
 #[derive(Default)]
 pub(crate) struct Friends {
     auth: Rc<RefCell<Auth>>,
     context: Rc<Context>,
-    screen: Rc<Cell<CurrentScreen>>,
-    pub(crate) index: usize,
-    pub(crate) index_max: usize,
-    pub(crate) friends_list: Vec<String>,
+    screen: Rc<Router>,
+    pub(crate) pager: Pager,
+    pub(crate) confirm_remove: bool,
+    pub(crate) friends_list: Vec<Friend>,
+    fetched: Option<Instant>,
+    pub(crate) requests_index: usize,
     pub(crate) friend_tmp: String,
+    pub(crate) suggestions: Vec<Profile>,
+    pub(crate) suggestion_index: Option<usize>,
+    search_at: Option<Instant>,
     pub(crate) blink: bool,
 }
 
@@ -27,7 +122,7 @@ impl Friends {
     pub(crate) fn new(
         context: Rc<Context>,
         auth: Rc<RefCell<Auth>>,
-        screen: Rc<Cell<CurrentScreen>>,
+        screen: Rc<Router>,
     ) -> Self {
         Friends {
             auth,
@@ -36,158 +131,266 @@ impl Friends {
             ..Default::default()
         }
     }
-    ///Get friends list with pending attribute if friend is not accepted yet
+    ///Get friends list if the one we have is too old
+    pub(crate) async fn refresh_friends(&mut self) -> Result<()> {
+        match self.fetched {
+            Some(fetched) if fetched.elapsed() < FRIENDS_REFRESH => Ok(()),
+            _ => self.get_indexed_friends().await,
+        }
+    }
+    ///Get friends list with the status of each friendship
     pub(crate) async fn get_indexed_friends(&mut self) -> Result<()> {
-        let friends_list = self.get_all_friends().await?;
-        let mut printable: Vec<String> = vec![];
-        let mut _str_tmp: String = String::new();
-        for element in &friends_list[..] {
-            _str_tmp = element.0.clone();
-            if !element.1 {
-                _str_tmp += " (Pending)";
-            }
-            printable.push(_str_tmp);
+        //Queued updates may be older than the fetched status, they must not overwrite it
+        self.update_presence();
+        let selected_id = self.selected_friend().map(|friend| friend.id);
+        self.friends_list = self.get_all_friends().await?;
+        self.fetched = Some(Instant::now());
+        self.sort_friends(selected_id);
+        if self.requests_index >= self.incoming_requests().len() {
+            self.requests_index = 0;
         }
-        self.friends_list = printable;
         Ok(())
     }
+    ///Friend requests sent to us and waiting for an answer
+    pub(crate) fn incoming_requests(&self) -> Vec<&Friend> {
+        self.friends_list
+            .iter()
+            .filter(|friend| friend.status == FriendStatus::Incoming)
+            .collect()
+    }
     ///Update friends display depending on the terminal's size
     pub(crate) async fn update_friends_index(
         &mut self,
         terminal: &mut ratatui::DefaultTerminal,
     ) -> Result<()> {
-        self.get_indexed_friends().await?;
-        let height: usize = (terminal.get_frame().area().height - 2) as usize;
-        let len = self.friends_list.len();
-        let modulo: usize = match height {
-            0 => 0,
-            _ => match len % height {
-                0 => 0,
-                _ => 1,
-            },
-        };
-        if height < len && height != 0 {
-            self.index_max = len / height + modulo;
-        } else {
-            self.index_max = 0;
+        self.refresh_friends().await?;
+        let page_height = terminal.get_frame().area().height.saturating_sub(2) as usize;
+        self.pager.update(self.friends_list.len(), page_height);
+        Ok(())
+    }
+    ///Apply the status changes received from the chat
+    pub(crate) fn update_presence(&mut self) {
+        let mut changed = false;
+        if let Some(receiver) = self.auth.borrow_mut().presence.as_mut() {
+            while let Ok((id, presence)) = receiver.try_recv() {
+                self.context.users.borrow_mut().set_presence(id, presence);
+                if let Some(friend) = self.friends_list.iter_mut().find(|friend| friend.id == id) {
+                    friend.presence = presence;
+                    changed = true;
+                }
+            }
         }
-        if self.index > self.index_max {
-            self.index = 0;
+        if changed {
+            let selected_id = self.selected_friend().map(|friend| friend.id);
+            self.sort_friends(selected_id);
         }
-        Ok(())
     }
-    pub(crate) async fn add_friend(&mut self) -> Result<()> {
-        if poll(Duration::from_millis(500))? {
-            let event = event::read()?;
+    ///Sort friends online first, keeping the cursor on the same friend
+    fn sort_friends(&mut self, selected_id: Option<u64>) {
+        self.friends_list
+            .sort_by_key(|friend| (friend.presence.rank(), friend.name.to_lowercase()));
+        self.pager.set_len(self.friends_list.len());
+        let selected = selected_id
+            .and_then(|id| self.friends_list.iter().position(|friend| friend.id == id))
+            .unwrap_or(self.pager.selected);
+        self.pager.select(selected);
+    }
+    pub(crate) fn selected_friend(&self) -> Option<&Friend> {
+        self.friends_list.get(self.pager.selected)
+    }
+    ///Type a name and pick a user among the suggestions
+    ///
+    /// Suggestions are requested once the user stopped typing for SEARCH_DEBOUNCE
+    ///
+    /// #Parameters
+    /// - help: Help overlay, which keeps the keys from the screen while it is displayed
+    pub(crate) async fn add_friend(&mut self, help: &Help) -> Result<()> {
+        let timeout = match self.search_at {
+            Some(search_at) => search_at
+                .saturating_duration_since(Instant::now())
+                .min(Duration::from_millis(500)),
+            _ => Duration::from_millis(500),
+        };
+        let screen = self.screen.get();
+        if poll(timeout)?
+            && let Some(event) = help.read_event(screen)?
+        {
             if should_exit(&event)? {
-                self.friend_tmp.clear();
-                self.screen.set(CurrentScreen::FriendsDisplay);
+                self.clear_search();
+                self.screen.back();
             } else if let Event::Key(eventkey) = event {
-                match eventkey.code {
-                    KeyCode::Backspace => {
+                match (keymap::action(screen, eventkey.code), eventkey.code) {
+                    (Some(Action::Erase), _) => {
                         self.friend_tmp.pop();
+                        self.search_at = Some(Instant::now() + SEARCH_DEBOUNCE);
+                    }
+                    (None, KeyCode::Char(c)) => {
+                        self.friend_tmp.push(c);
+                        self.search_at = Some(Instant::now() + SEARCH_DEBOUNCE);
+                    }
+                    (Some(Action::Previous), _) => {
+                        self.suggestion_index = match self.suggestion_index {
+                            Some(0) | None => None,
+                            Some(index) => Some(index - 1),
+                        }
                     }
-                    KeyCode::Char(c) => self.friend_tmp.push(c),
-                    KeyCode::Enter => {
-                        self.send_friend_request().await?;
+                    (Some(Action::Next), _) if !self.suggestions.is_empty() => {
+                        self.suggestion_index = match self.suggestion_index {
+                            None => Some(0),
+                            Some(index) => Some((index + 1).min(self.suggestions.len() - 1)),
+                        }
+                    }
+                    (Some(Action::Complete), _) => {
+                        if let Some(profile) = self.selected_suggestion() {
+                            self.friend_tmp = profile.name.clone();
+                        }
+                    }
+                    (Some(Action::Submit), _) => {
+                        let id = match self.selected_suggestion() {
+                            Some(profile) => profile.id,
+                            _ => get_id(self.context.clone(), &self.friend_tmp).await?,
+                        };
+                        self.clear_search();
+                        self.send_friend_request(id).await?;
                         self.get_indexed_friends().await?;
                     }
                     _ => {}
                 }
             }
         }
+        if let Some(search_at) = self.search_at
+            && Instant::now() >= search_at
+        {
+            self.search_at = None;
+            self.update_suggestions().await?;
+        }
         self.tick();
         Ok(())
     }
-    pub(crate) async fn delete_friend(&mut self) -> Result<()> {
-        if poll(Duration::from_millis(500))? {
-            let event = event::read()?;
+    async fn update_suggestions(&mut self) -> Result<()> {
+        self.suggestion_index = None;
+        self.suggestions = match self.friend_tmp.is_empty() {
+            true => vec![],
+            false => search_users(self.context.clone(), &self.friend_tmp).await?,
+        };
+        Ok(())
+    }
+    pub(crate) fn selected_suggestion(&self) -> Option<&Profile> {
+        self.suggestions.get(self.suggestion_index?)
+    }
+    fn clear_search(&mut self) {
+        self.friend_tmp.clear();
+        self.suggestions.clear();
+        self.suggestion_index = None;
+        self.search_at = None;
+    }
+    ///Remove the friend under the cursor, once the removal has been confirmed
+    pub(crate) async fn remove_selected(&mut self) -> Result<()> {
+        self.confirm_remove = false;
+        if let Some(friend) = self.selected_friend() {
+            let id = friend.id;
+            self.send_friend_action(Method::DELETE, "remove", id).await?;
+            self.get_indexed_friends().await?;
+        }
+        Ok(())
+    }
+    ///Accept or decline the friend requests we received
+    ///
+    /// #Parameters
+    /// - help: Help overlay, which keeps the keys from the screen while it is displayed
+    pub(crate) async fn handle_requests(&mut self, help: &Help) -> Result<()> {
+        let screen = self.screen.get();
+        if poll(Duration::from_millis(500))?
+            && let Some(event) = help.read_event(screen)?
+        {
             if should_exit(&event)? {
-                self.friend_tmp.clear();
-                self.screen.set(CurrentScreen::FriendsDisplay);
+                self.screen.back();
             } else if let Event::Key(eventkey) = event {
-                match eventkey.code {
-                    KeyCode::Backspace => {
-                        self.friend_tmp.pop();
+                let requests_len = self.incoming_requests().len();
+                match keymap::action(screen, eventkey.code) {
+                    Some(Action::Previous) => {
+                        self.requests_index = self.requests_index.saturating_sub(1);
                     }
-                    KeyCode::Char(c) => self.friend_tmp.push(c),
-                    KeyCode::Enter => {
-                        self.send_delete_friend_request().await?;
-                        self.get_indexed_friends().await?;
+                    Some(Action::Next) if self.requests_index + 1 < requests_len => {
+                        self.requests_index += 1;
+                    }
+                    Some(Action::Accept) => {
+                        if let Some(friend) = self.incoming_requests().get(self.requests_index) {
+                            let id = friend.id;
+                            self.send_accept_request(id).await?;
+                            self.get_indexed_friends().await?;
+                        }
+                    }
+                    Some(Action::Decline) => {
+                        if let Some(friend) = self.incoming_requests().get(self.requests_index) {
+                            let id = friend.id;
+                            self.send_decline_request(id).await?;
+                            self.get_indexed_friends().await?;
+                        }
                     }
                     _ => {}
                 }
             }
         }
-        self.tick();
         Ok(())
     }
-    async fn send_friend_request(&mut self) -> Result<()> {
-        let mut map = HashMap::new();
-        let token = self.auth.borrow().token.to_string();
-        map.insert("token", &token);
-        let id = get_id_from_name(self.context.clone(), &self.friend_tmp).await?.to_string();
-        map.insert("friend_id", &id);
-        let url = format!("https://{}/api/friends/send_request", self.context.location);
-        let response = self
-            .context
-            .client
-            .post(url)
-            .header("content-type", "application/json")
-            .json(&map)
-            .send()
+    async fn send_friend_request(&mut self, id: u64) -> Result<()> {
+        self.send_friend_action(Method::POST, "send_request", id)
             .await?;
-        self.friend_tmp.clear();
-        match response.status().as_u16() {
-            200 => {
-                self.screen.set(CurrentScreen::FriendsDisplay);
-            }
-            _ => {
-                let message: serde_json::Value = response.json().await?;
-                if let Some(error_message) = message["message"].as_str() {
-                    return Err(anyhow!(error_message.to_string()));
-                }
-            }
-        }
+        self.screen.back();
         Ok(())
     }
-    async fn send_delete_friend_request(&mut self) -> Result<()> {
+    async fn send_accept_request(&self, friend_id: u64) -> Result<()> {
+        self.send_friend_action(Method::POST, "accept", friend_id)
+            .await
+    }
+    ///A declined request is removed from both users' lists
+    async fn send_decline_request(&self, friend_id: u64) -> Result<()> {
+        self.send_friend_action(Method::DELETE, "remove", friend_id)
+            .await
+    }
+    ///Send an authenticated request to one of the /api/friends endpoints
+    ///
+    /// #Errors
+    /// Returns the server's message if the response is not a success
+    async fn send_friend_action(
+        &self,
+        method: Method,
+        endpoint: &str,
+        friend_id: u64,
+    ) -> Result<()> {
         let mut map = HashMap::new();
         let token = self.auth.borrow().token.to_string();
-        map.insert("token", &token);
-        let id = get_id_from_name(self.context.clone(), &self.friend_tmp).await?.to_string();
-        map.insert("friend_id", &id);
-        let url = format!("https://{}/api/friends/remove", self.context.location);
+        map.insert("token", token);
+        map.insert("friend_id", friend_id.to_string());
+        let url = format!("https://{}/api/friends/{}", self.context.location, endpoint);
         let response = self
             .context
             .client
-            .delete(url)
+            .request(method, url)
             .header("content-type", "application/json")
             .json(&map)
             .send()
             .await?;
-        self.friend_tmp.clear();
         match response.status().as_u16() {
-            200 => {
-                self.screen.set(CurrentScreen::FriendsDisplay);
-            }
+            200 => Ok(()),
             _ => {
                 let message: serde_json::Value = response.json().await?;
-                if let Some(message) = message["message"].as_str() {
-                    return Err(anyhow!(message.to_string()));
+                match message["message"].as_str() {
+                    Some(error_message) => Err(anyhow!(error_message.to_string())),
+                    _ => Err(anyhow!("Friends request failed")),
                 }
             }
         }
-        Ok(())
     }
-    async fn get_all_friends(&self) -> Result<Vec<(String, bool)>> {
+    async fn get_all_friends(&self) -> Result<Vec<Friend>> {
         let url = format!(
             "https://{}/api/friends/get?user_id={}",
             self.context.location,
             self.auth.borrow().id
         );
         let response = self.context.client.get(url).send().await?;
-        let mut result: Vec<(String, bool)> = vec![];
+        let mut friendships: Vec<(u64, FriendStatus)> = vec![];
         match response.status().as_u16() {
             200 => {
                 let response_array: serde_json::Value = response.json().await?;
@@ -205,16 +408,16 @@ impl Friends {
                                 continue;
                             }
                         };
-                        let name = self.look_for_name(object).await?;
-                        match map["pending"].as_u64() {
-                            Some(0) => {
-                                result.push((name, true));
-                            }
-                            Some(1) => {
-                                result.push((name, false));
-                            }
-                            _ => {}
-                        }
+                        let id = self.look_for_id(object)?;
+                        let status = match map["pending"].as_u64() {
+                            Some(0) => FriendStatus::Accepted,
+                            Some(1) => match self.requester_id(object) {
+                                Some(requester) if requester == id => FriendStatus::Incoming,
+                                _ => FriendStatus::Outgoing,
+                            },
+                            _ => continue,
+                        };
+                        friendships.push((id, status));
                     }
                 }
             }
@@ -225,40 +428,48 @@ impl Friends {
                 eprintln!("Error {} from server :(", err);
             }
         }
+        let ids: Vec<u64> = friendships.iter().map(|(id, _)| *id).collect();
+        let mut profiles = get_users(self.context.clone(), &ids).await;
+        let result = friendships
+            .into_iter()
+            .map(|(id, status)| match profiles.remove(&id) {
+                Some(profile) => Friend {
+                    id,
+                    name: profile.name,
+                    status,
+                    presence: profile.presence,
+                },
+                _ => Friend {
+                    id,
+                    name: format!("#{id}"),
+                    status,
+                    presence: Presence::default(),
+                },
+            })
+            .collect();
         Ok(result)
     }
-    ///Given a json containing two user_id including ours, return other id name
+    ///Given a json containing two user_id including ours, return the other id
     ///
     /// The server sends a friends list containing 2 ids: ours and the friend's
     ///
-    /// Function first compares to both id to get the friend's id
-    /// then sends a request to get name from id
-    async fn look_for_name(&self, object: &serde_json::Value) -> Result<String> {
-        let id_to_find = match object["user1_id"].as_u64() {
-            Some(user1) => {
-                let id = self.auth.borrow().id;
-                if user1 != id {
-                    user1
-                } else {
-                    match object["user2_id"].as_u64() {
-                        Some(user2) => {
-                            if user2 != id {
-                                user2
-                            } else {
-                                return Err(anyhow!("from user ids"));
-                            }
-                        }
-                        _ => {
-                            return Err(anyhow!("from user ids"));
-                        }
-                    }
-                }
-            }
-            _ => {
-                return Err(anyhow!("from user ids"));
-            }
-        };
-        get_name_from_id(self.context.clone(), id_to_find).await
+    /// Function compares both ids to ours to get the friend's id
+    fn look_for_id(&self, object: &serde_json::Value) -> Result<u64> {
+        let id = self.auth.borrow().id;
+        match (object["user1_id"].as_u64(), object["user2_id"].as_u64()) {
+            (Some(user1), _) if user1 != id => Ok(user1),
+            (Some(_), Some(user2)) if user2 != id => Ok(user2),
+            _ => Err(anyhow!("from user ids")),
+        }
+    }
+    ///Id of the user who sent a pending request
+    ///
+    /// Uses the server's sender field when present,
+    /// otherwise the request was created with the sender as user1
+    fn requester_id(&self, object: &serde_json::Value) -> Option<u64> {
+        object["sender_id"]
+            .as_u64()
+            .or_else(|| object["user1_id"].as_u64())
     }
     pub(crate) fn tick(&mut self) {
         self.blink = !self.blink;
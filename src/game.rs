@@ -1,9 +1,19 @@
 use crate::Infos;
-use crate::utils::{get_name_from_id, should_exit};
-use crate::{Auth, Context};
+use crate::court::CourtConfig;
+use crate::analytics::MatchAnalytics;
+use crate::directory::get_user;
+use crate::history::LocalMatch;
+use crate::keymap::{self, Action, Help};
+use crate::outcome::{GameEnd, GameOutcome};
+use crate::pause::{PAUSE_MESSAGE, Pause, PauseNotice, RESUME_MESSAGE};
+use crate::rematch::RematchState;
+use crate::replay::{Recorder, save_replay};
+use crate::spectate::LiveGame;
+use crate::utils::{now_timestamp, should_exit};
+use crate::{Auth, Context, CurrentScreen};
 use anyhow::{Result, anyhow};
 use bytes::Bytes;
-use crossterm::event::{self, Event, KeyCode, KeyEventKind, poll};
+use crossterm::event::{Event, KeyEventKind, poll};
 use futures::stream::StreamExt;
 use futures_util::{
     SinkExt,
@@ -11,6 +21,8 @@ use futures_util::{
 };
 use std::cell::RefCell;
 use std::rc::Rc;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::time::{Duration, Instant};
 use tokio::net::TcpStream;
 use tokio::sync::{mpsc, watch};
@@ -24,6 +36,10 @@ type GameChannel = (
     watch::Receiver<(Option<Bytes>, Option<Utf8Bytes>)>,
 );
 pub(crate) type WsStream = WebSocketStream<MaybeTlsStream<TcpStream>>;
+///Message telling the game server the player leaves the game
+const FORFEIT_MESSAGE: &str = r#"{"type":"forfeit"}"#;
+///Time without news from the game server after which the connection is considered lost
+pub(crate) const CONNECTION_TIMEOUT: Duration = Duration::from_secs(5);
 
 #[derive(Default)]
 pub(crate) struct Game {
@@ -31,14 +47,40 @@ pub(crate) struct Game {
     context: Rc<Context>,
     game_id: String,
     pub(crate) opponent_name: String,
+    pub(crate) opponent_id: u64,
     player_side: u64,
     pub(crate) receiver: Option<watch::Receiver<(Option<Bytes>, Option<Utf8Bytes>)>>,
     pub(crate) game_checker: Option<watch::Receiver<bool>>,
     pub(crate) game_stats: GameStats,
     pub(crate) game_sender: Option<mpsc::Sender<u8>>,
+    started_at: u64,
+    recorder: Recorder,
+    ///Watching a game of other players, without sending paddle input
+    pub(crate) spectator: bool,
+    ///Left player, when spectating
+    pub(crate) player_name: String,
+    player_id: u64,
+    ///Tournament the game belongs to
+    pub(crate) tournament: Option<u64>,
+    ended_at: u64,
+    pub(crate) analytics: MatchAnalytics,
+    pub(crate) outcome: GameOutcome,
+    pub(crate) end: GameEnd,
+    ///Set while the player is asked to confirm leaving the game
+    pub(crate) forfeit_prompt: Arc<AtomicBool>,
+    pub(crate) pause: Option<Pause>,
+    ///Geometry of the court sent by the server
+    pub(crate) config: CourtConfig,
+    ///Set while the game is paused, to stop sending inputs
+    paused: Arc<AtomicBool>,
+    pub(crate) rematch: RematchState,
+    ///Why the game could not be kept in the local history
+    pub(crate) save_error: Option<String>,
+    ///Help overlay of the main structure, read by the task reading the keys
+    help: Help,
 }
 
-#[derive(Default)]
+#[derive(Default, Clone, Copy)]
 pub(crate) struct GameStats {
     pub(crate) left_y: f32,
     pub(crate) right_y: f32,
@@ -72,7 +114,7 @@ impl Game {
             Some(id) => id,
             _ => return Err(anyhow!("No opponent id in response")),
         };
-        let opponent_name: String = get_name_from_id(info.context.clone(), opponent_id).await?;
+        let opponent_name: String = get_user(info.context.clone(), opponent_id).await?.name;
         let player_side: u64 = match value["playerSide"].as_u64() {
             Some(nbr) => nbr,
             _ => return Err(anyhow!("No player Id in response")),
@@ -83,25 +125,73 @@ impl Game {
             game_id,
             player_side,
             opponent_name,
+            opponent_id,
+            config: CourtConfig::from_value(&value["config"]),
+            help: info.help.clone(),
             ..Default::default()
         })
     }
+    ///Creates a game instance to watch a running game
+    ///
+    /// The left player is displayed as the player, the right one as the opponent
+    pub(crate) fn spectate(info: &Infos, live: &LiveGame) -> Game {
+        Game {
+            context: info.context.clone(),
+            auth: info.authent.clone(),
+            game_id: live.id.clone(),
+            opponent_name: live.player2_name.clone(),
+            spectator: true,
+            player_name: live.player1_name.clone(),
+            player_id: live.player1_id,
+            config: live.config,
+            help: info.help.clone(),
+            ..Default::default()
+        }
+    }
     /// Start a new game
     pub(crate) async fn start_game(&mut self) -> Result<()> {
         let ws_stream = self.connect_wss().await?;
         self.split_and_spawn_sockets(ws_stream).await?;
+        self.started_at = now_timestamp();
         Ok(())
     }
+    ///Summary of the finished game to keep in the local history
+    pub(crate) fn local_record(&self) -> LocalMatch {
+        LocalMatch {
+            opponent: self.opponent_name.clone(),
+            score: self.game_stats.player1_score,
+            opponent_score: self.game_stats.player2_score,
+            won: self.game_stats.winner,
+            started_at: self.started_at,
+            ended_at: self.ended_at,
+            server: self.context.location.clone(),
+        }
+    }
+    ///Length of the finished game, formatted as minutes:seconds
+    pub(crate) fn printable_duration(&self) -> String {
+        let seconds = self.ended_at.saturating_sub(self.started_at);
+        format!("{}:{:02}", seconds / 60, seconds % 60)
+    }
+    ///Write the frames received during the game to its replay file
+    pub(crate) fn save_replay(&self) -> Result<()> {
+        save_replay(self.started_at, &self.opponent_name, &self.recorder.frames)
+    }
     ///Initiate websocket connection with game server
     async fn connect_wss(&self) -> Result<WsStream> {
-        let url = format!(
-            "https://{}/api/start-game/{}",
-            self.context.location, self.game_id
-        );
-        self.context.client.post(url).send().await?;
+        let side = match self.spectator {
+            true => "spectate".to_string(),
+            false => {
+                let url = format!(
+                    "https://{}/api/start-game/{}",
+                    self.context.location, self.game_id
+                );
+                self.context.client.post(url).send().await?;
+                self.player_side.to_string()
+            }
+        };
         let request = format!(
             "wss://{}/api/game/{}/{}",
-            self.context.location, self.game_id, self.player_side
+            self.context.location, self.game_id, side
         )
         .into_client_request()?;
         let connector = Connector::NativeTls(
@@ -126,8 +216,15 @@ impl Game {
             watch::channel(true);
         self.game_checker = Some(game_checker);
         let socket_checker = game_sender.subscribe();
+        let spectator = self.spectator;
+        let prompts = (self.forfeit_prompt.clone(), self.paused.clone());
+        let help = self.help.clone();
         tokio::task::spawn(async move {
-            if let Err(e) = Self::send_game(ws_write, receiver, game_sender).await {
+            let result = match spectator {
+                true => Self::watch_game(ws_write, receiver, game_sender, help).await,
+                false => Self::send_game(ws_write, receiver, game_sender, prompts, help).await,
+            };
+            if let Err(e) = result {
                 eprintln!("Error: {}", e);
             }
         });
@@ -137,28 +234,81 @@ impl Game {
         self.game_sender = Some(sender);
         Ok(())
     }
-    /// Get the winner's name and send shutdown signal to spawned task
+    ///Read the outcome of the game and send shutdown signal to spawned task
+    ///
+    /// When spectating, winner tells whether the left player won
     ///
     /// #Parameters:
-    /// - text: text sent by the server containing the id of the winner
+    /// - text: end of game message sent by the server
     /// - sender: sender to use to shut down task sending game events to server
+    ///
+    /// #Return
+    /// false if the message holds no winner, the game then going on
+    ///
+    /// #Errors
+    /// Returns an error if the task sending game events can not be shut down
     pub(crate) async fn end_game(
         &mut self,
         text: Utf8Bytes,
         sender: mpsc::Sender<u8>,
-    ) -> Result<()> {
-        let value = serde_json::to_string(text.as_str())?;
-        match value.find(&self.auth.borrow().id.to_string()) {
-            Some(_) => self.game_stats.winner = true,
-            _ => self.game_stats.winner = false,
+    ) -> Result<bool> {
+        let Ok(outcome) = GameOutcome::parse(text.as_str()) else {
+            return Ok(false);
         };
+        self.ended_at = now_timestamp();
         let u: u8 = 1;
         sender.send(u).await?;
-        Ok(())
+        let id = match self.spectator {
+            true => self.player_id,
+            false => self.auth.borrow().id,
+        };
+        self.game_stats.winner = outcome.won_by(id);
+        self.end = GameEnd::from_outcome(&outcome, id);
+        if let Some((player1_score, player2_score)) = outcome.scores {
+            self.game_stats.player1_score = player1_score;
+            self.game_stats.player2_score = player2_score;
+        }
+        self.outcome = outcome;
+        Ok(true)
+    }
+    ///Apply a text frame of the server announcing a pause or its end
+    ///
+    /// #Return
+    /// false if the frame is not about pauses
+    pub(crate) fn apply_pause_notice(&mut self, text: &str) -> bool {
+        self.pause = match PauseNotice::parse(text) {
+            Some(PauseNotice::Paused { by, timeout }) => Some(Pause::new(by, timeout)),
+            Some(PauseNotice::Resumed) => None,
+            _ => return false,
+        };
+        self.paused.store(self.pause.is_some(), Ordering::Relaxed);
+        true
+    }
+    ///Name of the player who paused the game, "You" for the player
+    pub(crate) fn pauser_name(&self, by: u64) -> &str {
+        if !self.spectator && by == self.auth.borrow().id {
+            "You"
+        } else if self.spectator && by == self.player_id {
+            &self.player_name
+        } else {
+            &self.opponent_name
+        }
+    }
+    ///End the game without a message of the server, as a loss
+    ///
+    /// #Parameters:
+    /// - end: Forfeited when the player left, ConnectionLost when the server stopped answering
+    pub(crate) async fn abort(&mut self, end: GameEnd, sender: mpsc::Sender<u8>) {
+        self.ended_at = now_timestamp();
+        self.game_stats.winner = false;
+        self.end = end;
+        let _ = sender.send(1).await;
     }
     pub(crate) fn decode_and_update(&mut self, msg: Bytes) -> Result<()> {
         if msg.len() == 26 {
             self.game_stats = Self::decode(msg)?;
+            self.analytics.update(&self.game_stats);
+            self.recorder.push(&self.game_stats);
         }
         Ok(())
     }
@@ -192,10 +342,15 @@ impl Game {
     /// - ws_write: Writing part of the game websocket
     /// - receiver: End_game signal catcher
     /// - game_sender: Closer of the game websocket's reading part
+    /// - prompts: Set while the player is asked to confirm leaving the game, and while the game
+    ///   is paused
+    /// - help: Help overlay, which keeps the keys from the game while it is displayed
     async fn send_game(
         mut ws_write: SplitSink<WsStream, Message>,
         mut receiver: mpsc::Receiver<u8>,
         game_sender: watch::Sender<bool>,
+        (forfeit_prompt, paused): (Arc<AtomicBool>, Arc<AtomicBool>),
+        help: Help,
     ) -> Result<()> {
         let mut up: (bool, Instant, u128) = (false, std::time::Instant::now(), 0);
         let mut down: (bool, Instant, u128) = (false, std::time::Instant::now(), 0);
@@ -211,23 +366,42 @@ impl Game {
             if down.0 {
                 to_send.insert(0, 'D');
             }
-            if !to_send.is_empty() {
+            if !to_send.is_empty() && !paused.load(Ordering::Relaxed) {
                 let send_it = to_send.clone();
                 ws_write.send(send_it.into()).await?;
             }
-            if poll(Duration::from_millis(16))? {
-                let event = event::read()?;
-                if should_exit(&event)? {
-                    game_sender.send(true)?;
-                    break;
+            if poll(Duration::from_millis(16))?
+                && let Some(event) = help.read_event(CurrentScreen::PlayGame)?
+            {
+                if forfeit_prompt.load(Ordering::Relaxed) {
+                    match forfeit_answer(&event) {
+                        Some(true) => {
+                            ws_write.send(FORFEIT_MESSAGE.into()).await?;
+                            game_sender.send(true)?;
+                            break;
+                        }
+                        Some(false) => forfeit_prompt.store(false, Ordering::Relaxed),
+                        _ => {}
+                    }
+                } else if should_exit(&event)? {
+                    up.0 = false;
+                    down.0 = false;
+                    forfeit_prompt.store(true, Ordering::Relaxed);
                 } else if let Event::Key(key_event) = event {
-                    match key_event.code {
-                        KeyCode::Up => match key_event.kind {
+                    match keymap::action(CurrentScreen::PlayGame, key_event.code) {
+                        Some(Action::Pause) if key_event.kind == KeyEventKind::Press => {
+                            let message = match paused.load(Ordering::Relaxed) {
+                                true => RESUME_MESSAGE,
+                                false => PAUSE_MESSAGE,
+                            };
+                            ws_write.send(message.into()).await?;
+                        }
+                        Some(Action::Up) => match key_event.kind {
                             KeyEventKind::Press => up = (true, std::time::Instant::now(), 150),
                             KeyEventKind::Repeat => up = (true, std::time::Instant::now(), 150),
                             KeyEventKind::Release => up = (false, std::time::Instant::now(), 150),
                         },
-                        KeyCode::Down => match key_event.kind {
+                        Some(Action::Down) => match key_event.kind {
                             KeyEventKind::Press => down = (true, std::time::Instant::now(), 150),
                             KeyEventKind::Repeat => down = (true, std::time::Instant::now(), 150),
                             KeyEventKind::Release => down = (false, std::time::Instant::now(), 150),
@@ -249,6 +423,35 @@ impl Game {
         ws_write.close().await?;
         Ok(())
     }
+    ///Wait for the spectator to leave or for the end of the game, sending no input
+    ///
+    /// #Parameters:
+    /// - ws_write: Writing part of the game websocket, closed when leaving
+    /// - receiver: End_game signal catcher
+    /// - game_sender: Closer of the game websocket's reading part
+    /// - help: Help overlay, which keeps the keys from the game while it is displayed
+    async fn watch_game(
+        mut ws_write: SplitSink<WsStream, Message>,
+        mut receiver: mpsc::Receiver<u8>,
+        game_sender: watch::Sender<bool>,
+        help: Help,
+    ) -> Result<()> {
+        loop {
+            if receiver.try_recv().is_ok() {
+                break;
+            }
+            if poll(Duration::from_millis(16))?
+                && let Some(event) = help.read_event(CurrentScreen::PlayGame)?
+                && should_exit(&event)?
+            {
+                game_sender.send(true)?;
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(1)).await;
+        }
+        ws_write.close().await?;
+        Ok(())
+    }
     ///Read incoming game state from server
     ///
     /// #Parameters:
@@ -261,21 +464,19 @@ impl Game {
         socket_checker: watch::Receiver<bool>,
     ) {
         loop {
-            if let Some(msg) = ws_read.next().await {
-                match msg {
-                    Ok(Message::Binary(b)) => {
-                        if state_sender.send((Some(b), None)).is_err() {
-                            break;
-                        }
+            match ws_read.next().await {
+                Some(Ok(Message::Binary(b))) => {
+                    if state_sender.send((Some(b), None)).is_err() {
+                        break;
                     }
-                    Ok(Message::Text(s)) => {
-                        if state_sender.send((None, Some(s))).is_err() {
-                            break;
-                        }
+                }
+                Some(Ok(Message::Text(s))) => {
+                    if state_sender.send((None, Some(s))).is_err() {
+                        break;
                     }
-                    Ok(Message::Close(_)) => {}
-                    _ => {}
                 }
+                Some(Ok(_)) => {}
+                _ => break,
             }
             match socket_checker.has_changed() {
                 Ok(false) => {}
@@ -284,3 +485,17 @@ impl Game {
         }
     }
 }
+
+///Answer to the forfeit confirmation: Some(true) to leave, Some(false) to keep playing
+fn forfeit_answer(event: &Event) -> Option<bool> {
+    match event {
+        Event::Key(key_event) if key_event.kind != KeyEventKind::Release => {
+            match keymap::popup_action(CurrentScreen::PlayGame, key_event.code)? {
+                Action::Confirm => Some(true),
+                Action::Cancel => Some(false),
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
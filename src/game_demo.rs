@@ -1,3 +1,7 @@
+use crate::court::CourtConfig;
+use crate::game::GameStats;
+
+///Game played by two bots behind the menus, in the server's coordinates
 pub(crate) struct Demo {
     pub(crate) ball_x: f64,
     pub(crate) ball_y: f64,
@@ -5,24 +9,38 @@ pub(crate) struct Demo {
     pub(crate) ball_dy: f64,
     pub(crate) paddle_left_y: f64,
     pub(crate) paddle_right_y: f64,
+    pub(crate) config: CourtConfig,
 }
 
 impl Demo {
     pub(crate) fn update(&mut self) {
         self.ball_x += self.ball_dx;
         self.ball_y += self.ball_dy;
-        if self.ball_x <= 5.0 || self.ball_x >= 95.0 {
+        let left_face = self.config.left_paddle_x() + self.config.paddle_width;
+        let right_face = self.config.right_paddle_x();
+        let radius = self.config.ball_radius;
+        if self.ball_x - radius <= left_face || self.ball_x + radius >= right_face {
             self.ball_dx = -self.ball_dx;
         }
         if self.ball_y <= 0.0 || self.ball_y >= 100.0 {
             self.ball_dy = -self.ball_dy;
         }
+        let half_height = self.config.paddle_height / 2.0;
         if self.ball_x < 50.0 {
-            self.paddle_left_y += (self.ball_y - self.paddle_left_y - 5.0) * 0.12;
-            self.paddle_left_y = self.paddle_left_y.clamp(1.0, 90.0);
+            self.paddle_left_y += (self.ball_y - self.paddle_left_y) * 0.12;
+            self.paddle_left_y = self.paddle_left_y.clamp(half_height, 100.0 - half_height);
         } else {
-            self.paddle_right_y += (self.ball_y - self.paddle_right_y - 5.0) * 0.13;
-            self.paddle_right_y = self.paddle_right_y.clamp(1.0, 90.0);
+            self.paddle_right_y += (self.ball_y - self.paddle_right_y) * 0.13;
+            self.paddle_right_y = self.paddle_right_y.clamp(half_height, 100.0 - half_height);
+        }
+    }
+    pub(crate) fn stats(&self) -> GameStats {
+        GameStats {
+            left_y: self.paddle_left_y as f32,
+            right_y: self.paddle_right_y as f32,
+            ball_x: self.ball_x as f32,
+            ball_y: self.ball_y as f32,
+            ..Default::default()
         }
     }
 }
@@ -36,6 +54,7 @@ impl Default for Demo {
             ball_dy: 1.0,
             paddle_left_y: 50.0,
             paddle_right_y: 50.0,
+            config: CourtConfig::default(),
         }
     }
 }
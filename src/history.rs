@@ -0,0 +1,299 @@
+use crate::pager::Pager;
+use crate::utils::{format_timestamp, parse_date};
+use anyhow::{Result, anyhow};
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::PathBuf;
+
+///Name of the append-only file holding one finished match per line
+const HISTORY_FILE: &str = "history.jsonl";
+
+///A finished match, as recorded on this computer
+#[derive(Clone, Default)]
+pub(crate) struct LocalMatch {
+    pub(crate) opponent: String,
+    pub(crate) score: u8,
+    pub(crate) opponent_score: u8,
+    pub(crate) won: bool,
+    ///Unix timestamps of the beginning and the end of the match
+    pub(crate) started_at: u64,
+    pub(crate) ended_at: u64,
+    ///Location of the server the match was played on
+    pub(crate) server: String,
+}
+
+impl LocalMatch {
+    fn to_value(&self) -> serde_json::Value {
+        serde_json::json!({
+            "opponent": self.opponent,
+            "score": self.score,
+            "opponent_score": self.opponent_score,
+            "won": self.won,
+            "started_at": self.started_at,
+            "ended_at": self.ended_at,
+            "server": self.server,
+        })
+    }
+    fn from_value(value: &serde_json::Value) -> Option<LocalMatch> {
+        Some(LocalMatch {
+            opponent: value["opponent"].as_str()?.to_string(),
+            score: value["score"].as_u64()? as u8,
+            opponent_score: value["opponent_score"].as_u64()? as u8,
+            won: value["won"].as_bool()?,
+            started_at: value["started_at"].as_u64()?,
+            ended_at: value["ended_at"].as_u64()?,
+            server: value["server"].as_str().unwrap_or_default().to_string(),
+        })
+    }
+    pub(crate) fn printable_duration(&self) -> String {
+        let seconds = self.ended_at.saturating_sub(self.started_at);
+        format!("{}:{:02}", seconds / 60, seconds % 60)
+    }
+    pub(crate) fn printable_date(&self) -> String {
+        format_timestamp(self.started_at)
+    }
+}
+
+///Directory holding the client's data
+///
+/// PONG_DATA_DIR if set, else the XDG data directory
+pub(crate) fn data_dir() -> Result<PathBuf> {
+    if let Ok(dir) = std::env::var("PONG_DATA_DIR") {
+        return Ok(PathBuf::from(dir));
+    }
+    if let Ok(dir) = std::env::var("XDG_DATA_HOME") {
+        return Ok(PathBuf::from(dir).join("pong-cli"));
+    }
+    match std::env::var("HOME") {
+        Ok(home) => Ok(PathBuf::from(home).join(".local/share/pong-cli")),
+        _ => Err(anyhow!("no HOME directory to store data")),
+    }
+}
+
+///Append a finished match to the local history
+pub(crate) fn record_match(record: &LocalMatch) -> Result<()> {
+    let dir = data_dir()?;
+    fs::create_dir_all(&dir)?;
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(dir.join(HISTORY_FILE))?;
+    writeln!(file, "{}", record.to_value())?;
+    Ok(())
+}
+
+///Read every recorded match, most recent first
+///
+/// Lines which can not be parsed are skipped
+pub(crate) fn load_history() -> Result<Vec<LocalMatch>> {
+    let path = data_dir()?.join(HISTORY_FILE);
+    let content = match fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(vec![]),
+        Err(e) => return Err(e.into()),
+    };
+    let mut history: Vec<LocalMatch> = content
+        .lines()
+        .filter_map(|line| serde_json::from_str::<serde_json::Value>(line).ok())
+        .filter_map(|value| LocalMatch::from_value(&value))
+        .collect();
+    history.sort_by_key(|record| std::cmp::Reverse(record.started_at));
+    Ok(history)
+}
+
+///Criteria to select matches of the local history
+#[derive(Clone, Default)]
+pub(crate) struct HistoryFilter {
+    pub(crate) opponent: Option<String>,
+    ///Some(true) keeps wins only, Some(false) losses only
+    pub(crate) won: Option<bool>,
+    pub(crate) server: Option<String>,
+    ///Unix timestamp of the oldest match to keep
+    pub(crate) since: Option<u64>,
+    pub(crate) limit: Option<usize>,
+}
+
+impl HistoryFilter {
+    ///Parse the options of the history subcommand
+    ///
+    /// #Errors
+    /// Returns an error on unknown options or missing values
+    pub(crate) fn from_args(mut args: impl Iterator<Item = String>) -> Result<HistoryFilter> {
+        let mut filter = HistoryFilter::default();
+        while let Some(arg) = args.next() {
+            let mut value = |option: &str| match args.next() {
+                Some(value) => Ok(value),
+                _ => Err(anyhow!("missing value for {option}")),
+            };
+            match arg.as_str() {
+                "--opponent" => filter.opponent = Some(value(&arg)?),
+                "--server" => filter.server = Some(value(&arg)?),
+                "--since" => filter.since = Some(parse_date(&value(&arg)?)?),
+                "--limit" => filter.limit = Some(value(&arg)?.parse()?),
+                "--won" => filter.won = Some(true),
+                "--lost" => filter.won = Some(false),
+                _ => return Err(anyhow!("unknown option {arg}")),
+            }
+        }
+        Ok(filter)
+    }
+    pub(crate) fn matches(&self, record: &LocalMatch) -> bool {
+        self.opponent
+            .as_ref()
+            .is_none_or(|opponent| record.opponent.eq_ignore_ascii_case(opponent))
+            && self.won.is_none_or(|won| record.won == won)
+            && self
+                .server
+                .as_ref()
+                .is_none_or(|server| &record.server == server)
+            && self.since.is_none_or(|since| record.started_at >= since)
+    }
+    pub(crate) fn apply(&self, history: Vec<LocalMatch>) -> Vec<LocalMatch> {
+        history
+            .into_iter()
+            .filter(|record| self.matches(record))
+            .take(self.limit.unwrap_or(usize::MAX))
+            .collect()
+    }
+}
+
+///Run the history subcommand: print recorded matches on the standard output
+pub(crate) fn print_history(args: impl Iterator<Item = String>) -> Result<()> {
+    let filter = HistoryFilter::from_args(args)?;
+    let history = filter.apply(load_history()?);
+    if history.is_empty() {
+        println!("No match recorded");
+        return Ok(());
+    }
+    let wins = history.iter().filter(|record| record.won).count();
+    for record in &history {
+        println!(
+            "{}  {}  {:>2} - {:<2} {:<20} {:>6}  {}",
+            record.printable_date(),
+            if record.won { "W" } else { "L" },
+            record.score,
+            record.opponent_score,
+            record.opponent,
+            record.printable_duration(),
+            record.server,
+        );
+    }
+    println!(
+        "{} match(es), {} win(s), {} loss(es)",
+        history.len(),
+        wins,
+        history.len() - wins
+    );
+    Ok(())
+}
+
+///Result filter of the My history screen
+#[derive(Clone, Copy, PartialEq, Default)]
+pub(crate) enum ResultFilter {
+    #[default]
+    All,
+    Won,
+    Lost,
+}
+
+impl ResultFilter {
+    pub(crate) fn label(&self) -> &'static str {
+        match self {
+            ResultFilter::All => "all",
+            ResultFilter::Won => "wins",
+            ResultFilter::Lost => "losses",
+        }
+    }
+}
+
+///State of the My history screen
+#[derive(Default)]
+pub(crate) struct HistoryView {
+    history: Vec<LocalMatch>,
+    pub(crate) displayed: Vec<LocalMatch>,
+    pub(crate) result_filter: ResultFilter,
+    pub(crate) pager: Pager,
+}
+
+impl HistoryView {
+    pub(crate) fn load() -> Result<HistoryView> {
+        let mut view = HistoryView {
+            history: load_history()?,
+            ..Default::default()
+        };
+        view.update_displayed();
+        Ok(view)
+    }
+    ///Show all matches, then wins only, then losses only
+    pub(crate) fn cycle_filter(&mut self) {
+        self.result_filter = match self.result_filter {
+            ResultFilter::All => ResultFilter::Won,
+            ResultFilter::Won => ResultFilter::Lost,
+            ResultFilter::Lost => ResultFilter::All,
+        };
+        self.update_displayed();
+    }
+    fn update_displayed(&mut self) {
+        let filter = HistoryFilter {
+            won: match self.result_filter {
+                ResultFilter::All => None,
+                ResultFilter::Won => Some(true),
+                ResultFilter::Lost => Some(false),
+            },
+            ..Default::default()
+        };
+        self.displayed = filter.apply(self.history.clone());
+        self.pager.set_len(self.displayed.len());
+        self.pager.select(0);
+    }
+    pub(crate) fn selected_match(&self) -> Option<&LocalMatch> {
+        self.displayed.get(self.pager.selected)
+    }
+    ///Update history pages depending on the terminal's size
+    pub(crate) fn update_index(&mut self, terminal: &mut ratatui::DefaultTerminal) {
+        let page_height = terminal.get_frame().area().height.saturating_sub(3) as usize;
+        self.pager.update(self.displayed.len(), page_height);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<HistoryFilter> {
+        HistoryFilter::from_args(args.iter().map(|arg| arg.to_string()))
+    }
+
+    #[test]
+    fn no_options() {
+        let filter = parse(&[]).unwrap();
+        assert!(filter.opponent.is_none() && filter.won.is_none() && filter.server.is_none());
+        assert!(filter.since.is_none() && filter.limit.is_none());
+    }
+
+    #[test]
+    fn all_options() {
+        let filter = parse(&[
+            "--opponent", "bob", "--server", "localhost:8443", "--since", "2024-02-29", "--limit",
+            "5", "--won",
+        ])
+        .unwrap();
+        assert_eq!(filter.opponent.as_deref(), Some("bob"));
+        assert_eq!(filter.server.as_deref(), Some("localhost:8443"));
+        assert_eq!(filter.since, Some(1709164800));
+        assert_eq!(filter.limit, Some(5));
+        assert_eq!(filter.won, Some(true));
+        assert_eq!(parse(&["--won", "--lost"]).unwrap().won, Some(false));
+    }
+
+    #[test]
+    fn bad_options() {
+        assert!(parse(&["--opponent"]).is_err());
+        assert!(parse(&["--since", "2023-02-29"]).is_err());
+        assert!(parse(&["--since", "last week"]).is_err());
+        assert!(parse(&["--limit", "-1"]).is_err());
+        assert!(parse(&["--limit", "ten"]).is_err());
+        assert!(parse(&["--all"]).is_err());
+        assert!(parse(&["bob"]).is_err());
+    }
+}
@@ -1,19 +1,35 @@
 use anyhow::{Result, anyhow};
-use crossterm::event::{self, Event, KeyCode, poll};
+use crossterm::event::{self, Event, poll};
 use ratatui::{DefaultTerminal, Frame, buffer::Buffer, layout::Rect, widgets::Widget};
 use reqwest::header::HeaderMap;
-use std::cell::{Cell, RefCell};
+use std::cell::RefCell;
 use std::collections::HashMap;
 use std::rc::Rc;
+use std::sync::atomic::Ordering;
 use tokio::time::Duration;
 use crate::CurrentScreen;
+use crate::cast::CastWriter;
 use crate::context::Context;
 use crate::friends::Friends;
-use crate::game::Game;
+use crate::game::{CONNECTION_TIMEOUT, Game};
 use crate::game_demo::Demo;
+use crate::history::{HistoryView, record_match};
 use crate::infos_events::EventHandler;
+use crate::keymap::{self, Action, Help};
+use crate::leaderboard::Leaderboard;
+use crate::local_tournament::LocalOrganizer;
 use crate::login::Auth;
-use crate::screen_displays::ScreenDisplayer;
+use crate::menu::Menu;
+use crate::mouse;
+use crate::outcome::GameEnd;
+use crate::pause::PAUSE_REDRAW;
+use crate::profile::ProfileView;
+use crate::rematch::{RematchAction, RematchState, send_rematch};
+use crate::replay::ReplayPlayer;
+use crate::router::Router;
+use crate::screen_displays::{ScreenDisplayer, print_help};
+use crate::spectate::LiveGames;
+use crate::tournament::{Bracket, TournamentList};
 use crate::utils::should_exit;
 
 #[derive(Default)]
@@ -21,10 +37,24 @@ pub(crate)struct Infos {
     pub(crate) context: Rc<Context>,
     pub(crate) authent: Rc<RefCell<Auth>>,
     pub(crate) friend: Friends,
-    pub(crate) screen: Rc<Cell<CurrentScreen>>,
+    pub(crate) screen: Rc<Router>,
+    ///Screen the enter and leave hooks last ran for
+    shown: CurrentScreen,
     pub(crate) game: Game,
     pub(crate) demo: Demo,
-    pub(crate) post_error_screen: CurrentScreen,
+    pub(crate) profile: ProfileView,
+    pub(crate) leaderboard: Leaderboard,
+    pub(crate) history: HistoryView,
+    pub(crate) replay: ReplayPlayer,
+    pub(crate) live_games: LiveGames,
+    pub(crate) tournaments: TournamentList,
+    pub(crate) bracket: Bracket,
+    pub(crate) local: LocalOrganizer,
+    pub(crate) menu: Menu,
+    ///Help overlay, shared with the tasks reading the keys of a game
+    pub(crate) help: Help,
+    ///Recording of the session, when asked with --record
+    pub(crate) cast: Option<CastWriter>,
     pub(crate) error: String,
     pub(crate) exit: bool,
 }
@@ -33,7 +63,7 @@ impl Infos {
     pub(crate)fn new(
         context: Rc<Context>,
         auth: Rc<RefCell<Auth>>,
-        screen: Rc<Cell<CurrentScreen>>,
+        screen: Rc<Router>,
         friends: Friends,
     ) -> Infos {
         Infos {
@@ -46,11 +76,35 @@ impl Infos {
     }
     pub(crate) async fn run(mut self, terminal: &mut DefaultTerminal) -> Result<()> {
         while !self.exit {
+            self.follow_navigation().await;
             if self.screen.get() == CurrentScreen::FriendsDisplay {
                 self.friend.update_friends_index(terminal).await?;
             }
-            if let Err(e) = terminal.draw(|frame| self.draw(frame)) {
-                self.error(e.to_string());
+            if self.screen.get() == CurrentScreen::Profile {
+                self.profile.update_index(terminal);
+            }
+            if self.screen.get() == CurrentScreen::Leaderboard {
+                self.leaderboard.update_index(terminal);
+            }
+            if self.screen.get() == CurrentScreen::LocalHistory {
+                self.history.update_index(terminal);
+            }
+            if self.screen.get() == CurrentScreen::LiveGames {
+                self.live_games.update_index(terminal);
+            }
+            if self.screen.get() == CurrentScreen::Tournaments {
+                self.tournaments.update_index(terminal);
+            }
+            match terminal.draw(|frame| self.draw(frame)) {
+                Ok(frame) => {
+                    if let Some(cast) = &mut self.cast
+                        && let Err(e) = cast.capture(frame.buffer)
+                    {
+                        self.cast = None;
+                        self.error(format!("Recording stopped: {e}"));
+                    }
+                }
+                Err(e) => self.error(e.to_string()),
             }
             match self.screen.get() {
                 CurrentScreen::FirstScreen
@@ -71,6 +125,9 @@ impl Infos {
                 }
             }
         }
+        if let Some(cast) = self.cast.take() {
+            cast.finish()?;
+        }
         Ok(())
     }
     fn draw(&self, frame: &mut Frame) {
@@ -79,7 +136,7 @@ impl Infos {
     async fn handle_events(&mut self) -> Result<()> {
         match self.screen.get() {
             CurrentScreen::FirstScreen => {
-                if let Err(e) = self.handle_first_events().await {
+                if let Err(e) = self.handle_menu_events().await {
                     self.authent.borrow_mut().clear();
                     return Err(e);
                 }
@@ -96,24 +153,85 @@ impl Infos {
                     return Err(e);
                 }
             }
-            CurrentScreen::Welcome => self.handle_welcome_events()?,
-            CurrentScreen::GameChoice => self.handle_gamechoice_events()?,
-            CurrentScreen::SocialLife => self.handle_social_events().await?,
-            CurrentScreen::FriendsDisplay => self.handle_friends_events()?,
+            CurrentScreen::Welcome | CurrentScreen::GameChoice | CurrentScreen::SocialLife => {
+                self.handle_menu_events().await?
+            }
+            CurrentScreen::FriendsDisplay => self.handle_friends_events().await?,
             CurrentScreen::StartGame => self.launch_game().await?,
             CurrentScreen::EndGame => self.handle_endgame()?,
-            CurrentScreen::CreateGame => self.create_game("online").await?,
+            CurrentScreen::CreateGame => match self.bracket.starting {
+                true => self.create_game("tournament").await?,
+                false => self.create_game("online").await?,
+            },
             CurrentScreen::PlayGame => self.handle_game_events().await?,
             CurrentScreen::ErrorScreen => self.handle_errors().await?,
-            CurrentScreen::AddFriend => self.friend.add_friend().await?,
-            CurrentScreen::DeleteFriend => self.friend.delete_friend().await?,
+            CurrentScreen::AddFriend => self.friend.add_friend(&self.help).await?,
+            CurrentScreen::FriendRequests => self.friend.handle_requests(&self.help).await?,
+            CurrentScreen::Profile => self.handle_profile_events()?,
+            CurrentScreen::Leaderboard => self.handle_leaderboard_events().await?,
+            CurrentScreen::LocalHistory => self.handle_history_events()?,
+            CurrentScreen::Replay => self.handle_replay_events()?,
+            CurrentScreen::LiveGames => self.handle_live_games_events().await?,
+            CurrentScreen::Tournaments => self.handle_tournaments_events().await?,
+            CurrentScreen::Bracket => self.handle_bracket_events().await?,
+            CurrentScreen::LocalSetup => self.handle_local_setup_events()?,
+            CurrentScreen::LocalStandings => self.handle_local_standings_events()?,
+            CurrentScreen::LocalPlay => self.handle_local_play_events()?,
         }
         Ok(())
     }
+    ///Run the leave and enter hooks when the last event changed the displayed screen
+    async fn follow_navigation(&mut self) {
+        let screen = self.screen.get();
+        if screen == self.shown {
+            return;
+        }
+        let left = std::mem::replace(&mut self.shown, screen);
+        self.leave_screen(left);
+        //The screen left stays in the history when the new one was opened over it
+        let opened = self.screen.contains(left);
+        if let Err(e) = self.enter_screen(screen, opened).await {
+            self.screen.back();
+            self.error(e.to_string());
+        }
+    }
+    ///Hook run when a screen stops being displayed
+    fn leave_screen(&mut self, screen: CurrentScreen) {
+        self.help.close(screen);
+        if screen == CurrentScreen::Bracket {
+            self.bracket.ready = None;
+        }
+    }
+    ///Hook run when a screen starts being displayed, loading what it lists
+    ///
+    /// #Parameters
+    /// - opened: false when coming back from a screen opened over it
+    async fn enter_screen(&mut self, screen: CurrentScreen, opened: bool) -> Result<()> {
+        match screen {
+            CurrentScreen::LiveGames => {
+                self.live_games = LiveGames::fetch(self.context.clone()).await?;
+            }
+            CurrentScreen::Tournaments => {
+                self.tournaments = TournamentList::fetch(self.context.clone()).await?;
+            }
+            CurrentScreen::Leaderboard if opened => {
+                self.leaderboard = Leaderboard::fetch(self.context.clone()).await?;
+                self.leaderboard.jump_to(self.authent.borrow().id);
+            }
+            CurrentScreen::LocalHistory if opened => self.history = HistoryView::load()?,
+            _ => {}
+        }
+        Ok(())
+    }
+    ///Display a user's profile, coming back to the current screen when leaving
+    pub(crate) async fn open_profile(&mut self, id: u64) -> Result<()> {
+        self.profile = ProfileView::load(self.context.clone(), id).await?;
+        self.screen.push(CurrentScreen::Profile);
+        Ok(())
+    }
     pub(crate) fn error(&mut self, error: String) {
-        self.post_error_screen = self.screen.get();
         self.error = error;
-        self.screen.set(CurrentScreen::ErrorScreen);
+        self.screen.push(CurrentScreen::ErrorScreen);
     }
     async fn handle_errors(&mut self) -> Result<()> {
         loop {
@@ -122,11 +240,15 @@ impl Infos {
                 break;
             }
         }
-        self.screen.set(self.post_error_screen);
+        self.screen.back();
         Ok(())
     }
     pub(crate) async fn create_game(&mut self, mode: &str) -> Result<()> {
-        send_post_game_request(self, mode).await?;
+        let tournament = match std::mem::take(&mut self.bracket.starting) {
+            true => Some(self.bracket.tournament.id),
+            false => None,
+        };
+        send_post_game_request(self, mode, tournament).await?;
         loop {
             match poll(Duration::from_millis(16)) {
                 Ok(true) => {
@@ -143,7 +265,7 @@ impl Infos {
                     let event = event::read()?;
                     if let Ok(true) = should_exit(&event) {
                         self.send_remove_from_queue_request().await?;
-                        self.screen.set(CurrentScreen::GameChoice);
+                        self.screen.back();
                         return Ok(());
                     }
                 }
@@ -171,12 +293,13 @@ impl Infos {
             .try_recv()?;
         let game = Game::new(self, response).await?;
         self.game = game;
-        self.screen.set(crate::CurrentScreen::StartGame);
+        self.game.tournament = tournament;
+        self.screen.replace(CurrentScreen::StartGame);
         Ok(())
     }
     pub(crate) async fn launch_game(&mut self) -> Result<()> {
         self.game.start_game().await?;
-        self.screen.set(crate::CurrentScreen::PlayGame);
+        self.screen.replace(CurrentScreen::PlayGame);
         Ok(())
     }
     pub(crate) async fn handle_game_events(&mut self) -> Result<()> {
@@ -186,40 +309,157 @@ impl Infos {
                 return Err(anyhow!("State receiver is empty"));
             }
         };
-        if let Some(checker) = &mut self.game.game_checker
-            && let Ok(true) = checker.has_changed()
-        {
-            self.screen.set(crate::CurrentScreen::GameChoice);
+        let left = match &mut self.game.game_checker {
+            Some(checker) => matches!(checker.has_changed(), Ok(true)),
+            _ => false,
         };
-        if let Some(sender) = &self.game.game_sender {
-            state_receiver.changed().await?;
-            let (bytes, text) = state_receiver.borrow_and_update().clone();
-            match (bytes, text) {
-                (Some(bytes), _none) => {
-                    self.game.decode_and_update(bytes)?;
+        let Some(sender) = self.game.game_sender.clone() else {
+            return Ok(());
+        };
+        if left {
+            match self.game.spectator {
+                true => {
+                    self.screen.back();
                 }
-                (_none, Some(text)) => {
-                    self.game.end_game(text, sender.clone()).await?;
-                    self.screen.set(crate::CurrentScreen::EndGame);
+                false => {
+                    self.game.abort(GameEnd::Forfeited, sender).await;
+                    self.finish_game();
                 }
-                _ => {}
-            };
+            }
+            return Ok(());
+        }
+        let timeout = match &self.game.pause {
+            Some(_) => PAUSE_REDRAW,
+            _ => CONNECTION_TIMEOUT,
+        };
+        match tokio::time::timeout(timeout, state_receiver.changed()).await {
+            Ok(Ok(())) => {}
+            Err(_)
+                if self
+                    .game
+                    .pause
+                    .as_ref()
+                    .is_some_and(|pause| !pause.overdue(CONNECTION_TIMEOUT)) =>
+            {
+                return Ok(());
+            }
+            _ => {
+                self.game.abort(GameEnd::ConnectionLost, sender).await;
+                self.finish_game();
+                return Ok(());
+            }
         }
+        let (bytes, text) = state_receiver.borrow_and_update().clone();
+        match (bytes, text) {
+            (Some(bytes), _none) => {
+                self.game.decode_and_update(bytes)?;
+            }
+            (_none, Some(text)) if !self.game.apply_pause_notice(text.as_str()) => {
+                //Frames which are neither a pause nor a result are ignored
+                let ended = self.game.end_game(text, sender).await?;
+                if ended {
+                    self.finish_game();
+                }
+            }
+            _ => {}
+        };
         Ok(())
     }
+    ///Display the end-game screen, keeping the game in the local history
+    ///
+    /// Failing to write the history or the replay does not hide the result:
+    /// the error is shown on the end-game screen
+    fn finish_game(&mut self) {
+        self.game.forfeit_prompt.store(false, Ordering::Relaxed);
+        self.screen.replace(CurrentScreen::EndGame);
+        //Messages left from an earlier end-game screen are not about this game
+        if let Some(receiver) = self.authent.borrow_mut().rematch.as_mut() {
+            while receiver.try_recv().is_ok() {}
+        }
+        if !self.game.spectator && self.game.end != GameEnd::ConnectionLost {
+            let recorded = record_match(&self.game.local_record());
+            let saved = self.game.save_replay();
+            if let Err(e) = recorded.and(saved) {
+                self.game.save_error = Some(e.to_string());
+            }
+        }
+    }
     pub(crate) fn handle_endgame(&mut self) -> Result<()> {
-        if poll(Duration::from_millis(16))? {
-            let event = event::read()?;
+        let rematch = !self.game.spectator && self.game.tournament.is_none();
+        if rematch {
+            self.receive_rematch_messages();
+            if matches!(self.game.rematch, RematchState::Accepted | RematchState::Crossed) {
+                self.start_rematch();
+                return Ok(());
+            }
+        }
+        let screen = self.screen.get();
+        if poll(Duration::from_millis(16))?
+            && let Some(event) = self.help.read_event(screen)?
+        {
             if should_exit(&event)? {
-                self.screen.set(crate::CurrentScreen::GameChoice);
-            } else if let Event::Key(keyevent) = event
-                && keyevent.code == KeyCode::Enter
-            {
-                self.screen.set(crate::CurrentScreen::GameChoice);
+                self.leave_endgame()?;
+            } else if let Event::Key(keyevent) = event {
+                match keymap::action(screen, keyevent.code) {
+                    Some(Action::Back) => self.leave_endgame()?,
+                    Some(Action::Rematch) if rematch => self.propose_rematch()?,
+                    Some(Action::Decline) if self.game.rematch == RematchState::Received => {
+                        send_rematch(&self.authent, RematchAction::Decline, self.game.opponent_id)?;
+                        self.game.rematch = RematchState::None;
+                    }
+                    _ => {}
+                }
             }
         }
         Ok(())
     }
+    ///Apply the rematch messages sent by the opponent of the last game
+    fn receive_rematch_messages(&mut self) {
+        let mut auth = self.authent.borrow_mut();
+        let Some(receiver) = auth.rematch.as_mut() else {
+            return;
+        };
+        while let Ok((from, action)) = receiver.try_recv() {
+            if from == self.game.opponent_id {
+                self.game.rematch.receive(action);
+            }
+        }
+    }
+    ///Propose a rematch, or accept the one proposed by the opponent
+    fn propose_rematch(&mut self) -> Result<()> {
+        let opponent = self.game.opponent_id;
+        match self.game.rematch {
+            RematchState::Received => {
+                send_rematch(&self.authent, RematchAction::Accept, opponent)?;
+                self.start_rematch();
+            }
+            RematchState::Proposed => {}
+            _ => {
+                send_rematch(&self.authent, RematchAction::Propose, opponent)?;
+                self.game.rematch = RematchState::Proposed;
+            }
+        }
+        Ok(())
+    }
+    ///Start the accepted rematch, both players joining the online queue to meet again
+    fn start_rematch(&mut self) {
+        self.game.rematch = RematchState::None;
+        self.screen.replace(CurrentScreen::CreateGame);
+    }
+    ///Leave the end-game screen, withdrawing or declining a pending rematch
+    fn leave_endgame(&mut self) -> Result<()> {
+        let action = match self.game.rematch {
+            RematchState::Proposed => Some(RematchAction::Cancel),
+            RematchState::Received => Some(RematchAction::Decline),
+            _ => None,
+        };
+        self.game.rematch = RematchState::None;
+        if let Some(action) = action {
+            send_rematch(&self.authent, action, self.game.opponent_id)?;
+        }
+        self.screen.back();
+        Ok(())
+    }
     async fn send_remove_from_queue_request(&self) -> Result<()> {
         let mut map = HashMap::new();
         let mut headers = HeaderMap::new();
@@ -240,13 +480,14 @@ impl Infos {
 
 impl Widget for &Infos {
     fn render(self, area: Rect, buf: &mut Buffer) {
+        mouse::clear_targets();
         match self.screen.get() {
-            CurrentScreen::FirstScreen => self.display_first_screen(area, buf),
+            CurrentScreen::FirstScreen
+            | CurrentScreen::Welcome
+            | CurrentScreen::GameChoice
+            | CurrentScreen::SocialLife => self.display_menu_screen(area, buf),
             CurrentScreen::SignUp => self.display_signup_screen(area, buf),
             CurrentScreen::Login => self.display_login_screen(area, buf),
-            CurrentScreen::Welcome => self.display_welcome_screen(area, buf),
-            CurrentScreen::GameChoice => self.display_gamechoice_screen(area, buf),
-            CurrentScreen::SocialLife => self.display_social_screen(area, buf),
             CurrentScreen::FriendsDisplay => self.display_friends_screen(area, buf),
             CurrentScreen::StartGame => {}
             CurrentScreen::EndGame => self.display_endgame(area, buf),
@@ -254,18 +495,44 @@ impl Widget for &Infos {
             CurrentScreen::PlayGame => self.display_played_game(area, buf),
             CurrentScreen::ErrorScreen => self.display_error_screen(area, buf),
             CurrentScreen::AddFriend => self.display_addfriends_screen(area, buf),
-            CurrentScreen::DeleteFriend => self.display_delete_friends_screen(area, buf),
+            CurrentScreen::FriendRequests => self.display_friend_requests_screen(area, buf),
+            CurrentScreen::Profile => self.display_profile_screen(area, buf),
+            CurrentScreen::Leaderboard => self.display_leaderboard_screen(area, buf),
+            CurrentScreen::LocalHistory => self.display_history_screen(area, buf),
+            CurrentScreen::Replay => self.display_replay_screen(area, buf),
+            CurrentScreen::LiveGames => self.display_live_games_screen(area, buf),
+            CurrentScreen::Tournaments => self.display_tournaments_screen(area, buf),
+            CurrentScreen::Bracket => self.display_bracket_screen(area, buf),
+            CurrentScreen::LocalSetup => self.display_local_setup_screen(area, buf),
+            CurrentScreen::LocalStandings => self.display_local_standings_screen(area, buf),
+            CurrentScreen::LocalPlay => self.display_local_play_screen(area, buf),
+        }
+        if self.help.is_open(self.screen.get()) {
+            print_help(self.screen.get(), area, buf);
         }
     }
 }
 
-async fn send_post_game_request(game_main: &Infos, mode: &str) -> Result<()> {
+///Ask the server for a game
+///
+/// #Parameters
+/// - mode: "online" to join the queue, "tournament" to play the next match of a tournament
+/// - tournament: id of the tournament
+async fn send_post_game_request(
+    game_main: &Infos,
+    mode: &str,
+    tournament: Option<u64>,
+) -> Result<()> {
     let mut map = HashMap::new();
     let mut headers = HeaderMap::new();
     headers.insert("Content-Type", "application/json".parse()?);
     map.insert("mode", mode);
     let id: &str = &game_main.authent.borrow().id.to_string();
     map.insert("playerName", id);
+    let tournament_id = tournament.map(|tournament| tournament.to_string());
+    if let Some(tournament_id) = &tournament_id {
+        map.insert("tournamentId", tournament_id);
+    }
     let mut url = game_main.context.location.clone();
     url = format!("https://{url}/api/create-game");
     game_main
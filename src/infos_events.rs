@@ -1,118 +1,105 @@
 use crate::CurrentScreen;
 use crate::infos::Infos;
+use crate::keymap::{self, Action};
+use crate::friends::FriendStatus;
+use crate::local_tournament::NAME_MAX_LEN;
+use crate::replay::ReplayPlayer;
+use crate::spectate::LiveGames;
+use crate::tournament::{
+    Bracket, TOURNAMENT_SIZES, TournamentForm, TournamentList, create_tournament, join_tournament,
+};
+use crate::game::Game;
 use crate::login::{Field, create_guest_session, login, signup};
+use crate::menu::MenuAction;
+use crate::mouse;
+use crate::directory::refresh_stale;
 use crate::utils::should_exit;
 use anyhow::Result;
 use crossterm::event::{self, Event, KeyCode, KeyEventKind, poll};
 use std::time::Duration;
 
 pub(crate) trait EventHandler {
-    fn handle_welcome_events(&mut self) -> Result<()>;
-    fn handle_gamechoice_events(&mut self) -> Result<()>;
-    fn handle_friends_events(&mut self) -> Result<()>;
-    async fn handle_social_events(&mut self) -> Result<()>;
-    async fn handle_first_events(&mut self) -> Result<()>;
+    fn handle_profile_events(&mut self) -> Result<()>;
+    async fn handle_leaderboard_events(&mut self) -> Result<()>;
+    fn handle_history_events(&mut self) -> Result<()>;
+    fn handle_replay_events(&mut self) -> Result<()>;
+    async fn handle_live_games_events(&mut self) -> Result<()>;
+    async fn handle_tournaments_events(&mut self) -> Result<()>;
+    async fn handle_bracket_events(&mut self) -> Result<()>;
+    fn handle_local_setup_events(&mut self) -> Result<()>;
+    fn handle_local_standings_events(&mut self) -> Result<()>;
+    fn handle_local_play_events(&mut self) -> Result<()>;
+    async fn handle_friends_events(&mut self) -> Result<()>;
+    ///Events of the menu screens: FirstScreen, Welcome, GameChoice and SocialLife
+    async fn handle_menu_events(&mut self) -> Result<()>;
+    async fn activate_menu_entry(&mut self, action: MenuAction) -> Result<()>;
     async fn handle_signup_events(&mut self) -> Result<()>;
     async fn handle_login_events(&mut self) -> Result<()>;
 }
 
 impl EventHandler for Infos {
-    fn handle_welcome_events(&mut self) -> Result<()> {
-        let event = event::read()?;
-        if should_exit(&event)? {
-            self.exit = true;
-        } else if let Event::Key(key_event) = event
-            && key_event.kind == KeyEventKind::Press
-        {
-            match key_event.code {
-                KeyCode::Up => {
-                    self.screen.set(CurrentScreen::GameChoice);
-                }
-                KeyCode::Right => {
-                    self.screen.set(CurrentScreen::SocialLife);
-                }
-                _ => {}
-            }
+    async fn handle_menu_events(&mut self) -> Result<()> {
+        let screen = self.screen.get();
+        if screen == CurrentScreen::SocialLife {
+            self.friend.refresh_friends().await?;
         }
-        Ok(())
-    }
-    fn handle_gamechoice_events(&mut self) -> Result<()> {
-        let event = event::read()?;
+        let Some(event) = self.help.read_event(screen)? else {
+            return Ok(());
+        };
         if should_exit(&event)? {
-            self.exit = true;
-        } else if let Event::Key(key_event) = event
-            && key_event.kind == KeyEventKind::Press
-        {
-            match key_event.code {
-                KeyCode::Right => {
-                    self.screen.set(CurrentScreen::CreateGame);
-                }
-                KeyCode::Left => {
-                    self.screen.set(CurrentScreen::Welcome);
-                }
-                _ => {}
-            }
+            self.exit = !self.screen.back();
+        } else if let Some(action) = self.menu.handle_event(screen, &event) {
+            self.activate_menu_entry(action).await?;
         }
         Ok(())
     }
-    async fn handle_first_events(&mut self) -> Result<()> {
-        let event = event::read()?;
-        if should_exit(&event)? {
-            self.exit = true;
-        } else if let Event::Key(key_event) = event
-            && key_event.kind == KeyEventKind::Press
-        {
-            match key_event.code {
-                KeyCode::Up => {
-                    self.screen.set(CurrentScreen::SignUp);
-                }
-                KeyCode::Down => {
-                    self.screen.set(CurrentScreen::Login);
-                }
-                KeyCode::Right => {
-                    let credentials = match create_guest_session(self.context.clone()).await {
-                        Ok(credentials) => credentials,
-                        Err(e) => {
-                            self.authent.borrow_mut().clear();
-                            return Err(e);
-                        }
-                    };
-                    self.authent.borrow_mut().set_credentials(credentials);
-                    self.screen.set(CurrentScreen::Welcome);
-                }
-                _ => {}
+    async fn activate_menu_entry(&mut self, action: MenuAction) -> Result<()> {
+        match action {
+            MenuAction::SignUp => self.screen.push(CurrentScreen::SignUp),
+            MenuAction::Login => self.screen.push(CurrentScreen::Login),
+            MenuAction::LocalTournament => self.screen.push(CurrentScreen::LocalSetup),
+            MenuAction::Guest => {
+                let credentials = match create_guest_session(self.context.clone()).await {
+                    Ok(credentials) => credentials,
+                    Err(e) => {
+                        self.authent.borrow_mut().clear();
+                        return Err(e);
+                    }
+                };
+                self.authent.borrow_mut().set_credentials(credentials);
+                self.screen.deep_link(CurrentScreen::Welcome);
             }
-        }
-        Ok(())
-    }
-    async fn handle_social_events(&mut self) -> Result<()> {
-        self.friend.get_indexed_friends().await?;
-        let event = event::read()?;
-        if should_exit(&event)? {
-            self.exit = true;
-        } else if let Event::Key(key_event) = event {
-            match key_event.code {
-                KeyCode::Right => self.screen.set(CurrentScreen::FriendsDisplay),
-                KeyCode::Left => self.screen.set(CurrentScreen::Welcome),
-                _ => {}
+            MenuAction::Game => self.screen.push(CurrentScreen::GameChoice),
+            MenuAction::SocialLife => self.screen.push(CurrentScreen::SocialLife),
+            MenuAction::Profile => {
+                let id = self.authent.borrow().id;
+                self.open_profile(id).await?;
             }
+            MenuAction::Online => self.screen.push(CurrentScreen::CreateGame),
+            MenuAction::Watch => self.screen.push(CurrentScreen::LiveGames),
+            MenuAction::History => self.screen.push(CurrentScreen::LocalHistory),
+            MenuAction::Tournaments => self.screen.push(CurrentScreen::Tournaments),
+            MenuAction::Friends => self.screen.push(CurrentScreen::FriendsDisplay),
+            MenuAction::Leaderboard => self.screen.push(CurrentScreen::Leaderboard),
+            MenuAction::Back => self.exit = !self.screen.back(),
+            MenuAction::Quit => self.exit = true,
         }
         Ok(())
     }
     async fn handle_signup_events(&mut self) -> Result<()> {
-        if poll(Duration::from_millis(500))? {
-            let event = event::read()?;
+        let screen = self.screen.get();
+        if poll(Duration::from_millis(500))?
+            && let Some(event) = self.help.read_event(screen)?
+        {
             if should_exit(&event)? {
                 self.authent.borrow_mut().clear();
-                self.screen.set(CurrentScreen::FirstScreen);
+                self.screen.back();
             } else if let Event::Key(eventkey) = event {
-                match eventkey.code {
-                    KeyCode::Up => self.authent.borrow_mut().up_field_signup(),
-                    KeyCode::Down => self.authent.borrow_mut().down_field_signup(),
-                    KeyCode::Char(c) => self.authent.borrow_mut().add(c),
-                    KeyCode::Backspace => self.authent.borrow_mut().pop(),
-                    KeyCode::Tab => self.authent.borrow_mut().down_field_signup(),
-                    KeyCode::Enter => {
+                match (keymap::action(screen, eventkey.code), eventkey.code) {
+                    (Some(Action::Previous), _) => self.authent.borrow_mut().up_field_signup(),
+                    (Some(Action::Next), _) => self.authent.borrow_mut().down_field_signup(),
+                    (Some(Action::Erase), _) => self.authent.borrow_mut().pop(),
+                    (Some(Action::Submit), _) => {
                         if self.authent.borrow_mut().field == Field::Password {
                             let signup_infos = self.authent.borrow().get_signup_infos();
                             let credentials = match signup(self.context.clone(), signup_infos).await
@@ -124,11 +111,12 @@ impl EventHandler for Infos {
                                 }
                             };
                             self.authent.borrow_mut().set_credentials(credentials);
-                            self.screen.set(CurrentScreen::Welcome);
+                            self.screen.deep_link(CurrentScreen::Welcome);
                         } else {
                             self.authent.borrow_mut().down_field_signup()
                         }
                     }
+                    (None, KeyCode::Char(c)) => self.authent.borrow_mut().add(c),
                     _ => {}
                 }
             }
@@ -137,21 +125,19 @@ impl EventHandler for Infos {
         Ok(())
     }
     async fn handle_login_events(&mut self) -> Result<()> {
-        if poll(Duration::from_millis(500))? {
-            let event = event::read()?;
+        let screen = self.screen.get();
+        if poll(Duration::from_millis(500))?
+            && let Some(event) = self.help.read_event(screen)?
+        {
             if should_exit(&event)? {
                 self.authent.borrow_mut().clear();
-                self.screen.set(CurrentScreen::FirstScreen);
+                self.screen.back();
             } else if let Event::Key(eventkey) = event {
-                match eventkey.code {
-                    KeyCode::Up => self.authent.borrow_mut().up_field_login(),
-                    KeyCode::Down => self.authent.borrow_mut().down_field_login(),
-                    KeyCode::Char(c) => self.authent.borrow_mut().add(c),
-                    KeyCode::Backspace => {
-                        self.authent.borrow_mut().pop();
-                    }
-                    KeyCode::Tab => self.authent.borrow_mut().down_field_login(),
-                    KeyCode::Enter => {
+                match (keymap::action(screen, eventkey.code), eventkey.code) {
+                    (Some(Action::Previous), _) => self.authent.borrow_mut().up_field_login(),
+                    (Some(Action::Next), _) => self.authent.borrow_mut().down_field_login(),
+                    (Some(Action::Erase), _) => self.authent.borrow_mut().pop(),
+                    (Some(Action::Submit), _) => {
                         if self.authent.borrow_mut().field == Field::Totp {
                             let logins = self.authent.borrow().get_login_infos();
                             let credentials = match login(self.context.clone(), logins).await {
@@ -162,11 +148,12 @@ impl EventHandler for Infos {
                                 }
                             };
                             self.authent.borrow_mut().set_credentials(credentials);
-                            self.screen.set(CurrentScreen::Welcome);
+                            self.screen.deep_link(CurrentScreen::Welcome);
                         } else {
                             self.authent.borrow_mut().down_field_login()
                         }
                     }
+                    (None, KeyCode::Char(c)) => self.authent.borrow_mut().add(c),
                     _ => {}
                 }
             }
@@ -174,28 +161,386 @@ impl EventHandler for Infos {
         self.authent.borrow_mut().tick();
         Ok(())
     }
-    fn handle_friends_events(&mut self) -> Result<()> {
-        let event = event::read()?;
+    async fn handle_friends_events(&mut self) -> Result<()> {
+        if !poll(Duration::from_millis(500))? {
+            self.friend.update_presence();
+            return refresh_stale(self.context.clone()).await;
+        }
+        let screen = self.screen.get();
+        let Some(event) = self.help.read_event(screen)? else {
+            return Ok(());
+        };
+        if self.friend.confirm_remove {
+            if let Event::Key(key_event) = event
+                && key_event.kind == KeyEventKind::Press
+            {
+                match keymap::popup_action(screen, key_event.code) {
+                    Some(Action::Confirm) => self.friend.remove_selected().await?,
+                    _ => self.friend.confirm_remove = false,
+                }
+            }
+        } else if should_exit(&event)? {
+            self.screen.back();
+        } else if let Some(row) = mouse::clicked_row(&event) {
+            match row == self.friend.pager.selected {
+                true => {
+                    if let Some(friend) = self.friend.selected_friend() {
+                        self.open_profile(friend.id).await?;
+                    }
+                }
+                false => self.friend.pager.select(row),
+            }
+        } else if let Event::Key(key_event) = event
+            && key_event.kind == KeyEventKind::Press
+        {
+            match keymap::action(screen, key_event.code) {
+                Some(Action::Previous) => self.friend.pager.select_previous(),
+                Some(Action::Next) => self.friend.pager.select_next(),
+                Some(Action::PreviousPage) => self.friend.pager.previous_page(),
+                Some(Action::NextPage) => self.friend.pager.next_page(),
+                Some(Action::AddFriend) => self.screen.push(CurrentScreen::AddFriend),
+                Some(Action::Requests) => self.screen.push(CurrentScreen::FriendRequests),
+                Some(Action::Remove) => {
+                    self.friend.confirm_remove = self.friend.selected_friend().is_some();
+                }
+                Some(Action::Open) => {
+                    if let Some(friend) = self.friend.selected_friend() {
+                        self.open_profile(friend.id).await?;
+                    }
+                }
+                Some(Action::Invite) => {
+                    if let Some(friend) = self.friend.selected_friend()
+                        && friend.status == FriendStatus::Accepted
+                    {
+                        self.screen.push(CurrentScreen::CreateGame);
+                    }
+                }
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+    fn handle_profile_events(&mut self) -> Result<()> {
+        let screen = self.screen.get();
+        let Some(event) = self.help.read_event(screen)? else {
+            return Ok(());
+        };
+        if should_exit(&event)? {
+            self.screen.back();
+        } else if let Event::Key(key_event) = event
+            && key_event.kind == KeyEventKind::Press
+        {
+            match keymap::action(screen, key_event.code) {
+                Some(Action::NextPage) => self.profile.pager.next_page(),
+                Some(Action::PreviousPage) => self.profile.pager.previous_page(),
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+    async fn handle_leaderboard_events(&mut self) -> Result<()> {
+        let screen = self.screen.get();
+        let Some(event) = self.help.read_event(screen)? else {
+            return Ok(());
+        };
+        if should_exit(&event)? {
+            self.screen.back();
+        } else if let Event::Key(key_event) = event
+            && key_event.kind == KeyEventKind::Press
+        {
+            match keymap::action(screen, key_event.code) {
+                Some(Action::Previous) => self.leaderboard.pager.select_previous(),
+                Some(Action::Next) => self.leaderboard.pager.select_next(),
+                Some(Action::PreviousPage) => self.leaderboard.pager.previous_page(),
+                Some(Action::NextPage) => self.leaderboard.pager.next_page(),
+                Some(Action::Ranking) => self.leaderboard.toggle_ranking(),
+                Some(Action::Mine) => self.leaderboard.jump_to(self.authent.borrow().id),
+                Some(Action::Open) => {
+                    if let Some(entry) = self.leaderboard.selected_entry() {
+                        self.open_profile(entry.profile.id).await?;
+                    }
+                }
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+    fn handle_history_events(&mut self) -> Result<()> {
+        let screen = self.screen.get();
+        let Some(event) = self.help.read_event(screen)? else {
+            return Ok(());
+        };
+        if should_exit(&event)? {
+            self.screen.back();
+        } else if let Event::Key(key_event) = event
+            && key_event.kind == KeyEventKind::Press
+        {
+            match keymap::action(screen, key_event.code) {
+                Some(Action::Previous) => self.history.pager.select_previous(),
+                Some(Action::Next) => self.history.pager.select_next(),
+                Some(Action::PreviousPage) => self.history.pager.previous_page(),
+                Some(Action::NextPage) => self.history.pager.next_page(),
+                Some(Action::Filter) => self.history.cycle_filter(),
+                Some(Action::Open) => {
+                    if let Some(record) = self.history.selected_match() {
+                        self.replay = ReplayPlayer::load(record.started_at, &record.opponent)?;
+                        self.screen.push(CurrentScreen::Replay);
+                    }
+                }
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+    async fn handle_live_games_events(&mut self) -> Result<()> {
+        let screen = self.screen.get();
+        let Some(event) = self.help.read_event(screen)? else {
+            return Ok(());
+        };
+        if should_exit(&event)? {
+            self.screen.back();
+        } else if let Event::Key(key_event) = event
+            && key_event.kind == KeyEventKind::Press
+        {
+            match keymap::action(screen, key_event.code) {
+                Some(Action::Previous) => self.live_games.pager.select_previous(),
+                Some(Action::Next) => self.live_games.pager.select_next(),
+                Some(Action::PreviousPage) => self.live_games.pager.previous_page(),
+                Some(Action::NextPage) => self.live_games.pager.next_page(),
+                Some(Action::Refresh) => {
+                    self.live_games = LiveGames::fetch(self.context.clone()).await?;
+                }
+                Some(Action::Open) => {
+                    if let Some(live) = self.live_games.selected_game() {
+                        self.game = Game::spectate(self, live);
+                        self.screen.push(CurrentScreen::StartGame);
+                    }
+                }
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+    async fn handle_tournaments_events(&mut self) -> Result<()> {
+        let screen = self.screen.get();
+        let Some(event) = self.help.read_event(screen)? else {
+            return Ok(());
+        };
+        let Event::Key(key_event) = event else {
+            return Ok(());
+        };
+        if key_event.kind != KeyEventKind::Press {
+            return Ok(());
+        }
+        if let Some(form) = &mut self.tournaments.form {
+            match (keymap::popup_action(screen, key_event.code), key_event.code) {
+                (Some(Action::Cancel), _) => self.tournaments.form = None,
+                (Some(Action::Erase), _) => {
+                    form.name.pop();
+                }
+                (Some(Action::Previous), _) => form.size = form.size.saturating_sub(1),
+                (Some(Action::Next), _) => {
+                    form.size = (form.size + 1).min(TOURNAMENT_SIZES.len() - 1)
+                }
+                (Some(Action::Submit), _) if !form.name.trim().is_empty() => {
+                    let (name, size) = (form.name.trim().to_string(), form.size());
+                    let user_id = self.authent.borrow().id;
+                    let id = create_tournament(&self.context, user_id, &name, size).await?;
+                    self.tournaments.form = None;
+                    self.bracket = Bracket::load(self.context.clone(), id).await?;
+                    self.screen.push(CurrentScreen::Bracket);
+                }
+                (None, KeyCode::Char(c)) if form.name.chars().count() < 24 => form.name.push(c),
+                _ => {}
+            }
+            return Ok(());
+        }
+        if should_exit(&event)? {
+            self.screen.back();
+            return Ok(());
+        }
+        match keymap::action(screen, key_event.code) {
+            Some(Action::Previous) => self.tournaments.pager.select_previous(),
+            Some(Action::Next) => self.tournaments.pager.select_next(),
+            Some(Action::PreviousPage) => self.tournaments.pager.previous_page(),
+            Some(Action::NextPage) => self.tournaments.pager.next_page(),
+            Some(Action::Refresh) => {
+                self.tournaments = TournamentList::fetch(self.context.clone()).await?;
+            }
+            Some(Action::Create) => {
+                let username = self.authent.borrow().username.clone();
+                self.tournaments.form = Some(TournamentForm {
+                    name: match username.is_empty() {
+                        true => "Pong cup".to_string(),
+                        false => format!("{username}'s cup"),
+                    },
+                    ..Default::default()
+                });
+            }
+            Some(Action::Open) => {
+                if let Some(tournament) = self.tournaments.selected_tournament() {
+                    let id = tournament.id;
+                    let user_id = self.authent.borrow().id;
+                    if !tournament.players.contains(&user_id) {
+                        join_tournament(&self.context, user_id, id).await?;
+                    }
+                    self.bracket = Bracket::load(self.context.clone(), id).await?;
+                    self.screen.push(CurrentScreen::Bracket);
+                }
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+    async fn handle_bracket_events(&mut self) -> Result<()> {
+        if !poll(Duration::from_millis(500))? {
+            self.bracket.refresh(self.context.clone()).await?;
+            let id = self.authent.borrow().id;
+            self.bracket.check_ready(id);
+            if self.bracket.countdown_over() {
+                self.bracket.start_match();
+                self.screen.push(CurrentScreen::CreateGame);
+            }
+            return Ok(());
+        }
+        let screen = self.screen.get();
+        let Some(event) = self.help.read_event(screen)? else {
+            return Ok(());
+        };
+        if should_exit(&event)? {
+            self.screen.back();
+        } else if let Event::Key(key_event) = event
+            && key_event.kind == KeyEventKind::Press
+            && keymap::action(screen, key_event.code) == Some(Action::Open)
+            && self.bracket.ready.is_some()
+        {
+            self.bracket.start_match();
+            self.screen.push(CurrentScreen::CreateGame);
+        }
+        Ok(())
+    }
+    fn handle_local_setup_events(&mut self) -> Result<()> {
+        let screen = self.screen.get();
+        let Some(event) = self.help.read_event(screen)? else {
+            return Ok(());
+        };
+        if should_exit(&event)? {
+            self.screen.back();
+        } else if let Event::Key(key_event) = event
+            && key_event.kind == KeyEventKind::Press
+        {
+            let setup = &mut self.local.setup;
+            match (keymap::action(screen, key_event.code), key_event.code) {
+                (Some(Action::Erase), _) if setup.name.is_empty() => {
+                    setup.players.pop();
+                }
+                (Some(Action::Erase), _) => {
+                    setup.name.pop();
+                }
+                (Some(Action::Toggle), _) => setup.bot = !setup.bot,
+                (Some(Action::Previous | Action::Next), _) => {
+                    setup.format = setup.format.toggle()
+                }
+                (Some(Action::Submit), _) => {
+                    if setup.name.trim().is_empty() && !setup.bot {
+                        if setup.players.len() >= 2 {
+                            self.local.start();
+                            self.screen.push(CurrentScreen::LocalStandings);
+                        }
+                    } else {
+                        setup.add_player();
+                    }
+                }
+                (None, KeyCode::Char(c)) if setup.name.chars().count() < NAME_MAX_LEN => {
+                    setup.name.push(c)
+                }
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+    fn handle_local_standings_events(&mut self) -> Result<()> {
+        let screen = self.screen.get();
+        let Some(event) = self.help.read_event(screen)? else {
+            return Ok(());
+        };
+        if should_exit(&event)? {
+            self.local.tournament = None;
+            self.screen.back();
+        } else if let Event::Key(key_event) = event
+            && key_event.kind == KeyEventKind::Press
+            && keymap::action(screen, key_event.code) == Some(Action::Open)
+        {
+            self.local.play_next();
+            if self.local.playing.is_some() {
+                self.screen.push(CurrentScreen::LocalPlay);
+            }
+        }
+        Ok(())
+    }
+    fn handle_local_play_events(&mut self) -> Result<()> {
+        let screen = self.screen.get();
+        //The match is frozen while reading the help
+        if !self.help.is_open(screen) && self.local.update() {
+            self.screen.back();
+            return Ok(());
+        }
+        if !poll(Duration::from_millis(16))? {
+            return Ok(());
+        }
+        let Some(event) = self.help.read_event(screen)? else {
+            return Ok(());
+        };
         if should_exit(&event)? {
-            self.screen.set(CurrentScreen::SocialLife)
+            self.local.playing = None;
+            self.screen.back();
         } else if let Event::Key(key_event) = event {
-            match key_event.code {
-                KeyCode::Up => self.screen.set(CurrentScreen::AddFriend),
-                KeyCode::Down => self.screen.set(CurrentScreen::DeleteFriend),
-                KeyCode::Right => {
-                    if self.friend.index < self.friend.index_max {
-                        self.friend.index += 1
+            match keymap::action(screen, key_event.code) {
+                Some(Action::Pause) => {
+                    if key_event.kind == KeyEventKind::Press
+                        && let Some(playing) = &mut self.local.playing
+                    {
+                        playing.toggle_pause();
                     }
                 }
-                KeyCode::Left => {
-                    if self.friend.index > usize::MIN {
-                        self.friend.index -= 1
+                Some(action) => {
+                    let solo = match self.local.current_players() {
+                        Some((left, right)) if left.bot != right.bot => Some(!left.bot),
+                        _ => None,
+                    };
+                    if let Some(playing) = &mut self.local.playing {
+                        playing.keyboard.handle_key(action, key_event.kind, solo);
                     }
                 }
                 _ => {}
             }
-        } else if let Event::Resize(_, _) = event {
-            self.friend.index = 0;
+        }
+        Ok(())
+    }
+    fn handle_replay_events(&mut self) -> Result<()> {
+        self.replay.update();
+        if !event::poll(Duration::from_millis(16))? {
+            return Ok(());
+        }
+        let screen = self.screen.get();
+        let Some(event) = self.help.read_event(screen)? else {
+            return Ok(());
+        };
+        if should_exit(&event)? {
+            self.screen.back();
+        } else if let Event::Key(key_event) = event
+            && key_event.kind == KeyEventKind::Press
+        {
+            match keymap::action(screen, key_event.code) {
+                Some(Action::Pause) => self.replay.toggle_pause(),
+                Some(Action::SeekBackward) => self.replay.seek_backward(),
+                Some(Action::SeekForward) => self.replay.seek_forward(),
+                Some(Action::Faster) => self.replay.faster(),
+                Some(Action::Slower) => self.replay.slower(),
+                Some(Action::StepBackward) => self.replay.step_backward(),
+                Some(Action::StepForward) => self.replay.step_forward(),
+                _ => {}
+            }
         }
         Ok(())
     }
@@ -0,0 +1,512 @@
+use crate::CurrentScreen;
+use crate::mouse;
+use anyhow::Result;
+use crossterm::event::{self, Event, KeyCode, KeyEventKind, MouseEventKind};
+use std::sync::{Arc, Mutex};
+
+///What a key does on the screen it is bound on
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub(crate) enum Action {
+    Previous,
+    Next,
+    First,
+    Last,
+    PreviousPage,
+    NextPage,
+    ///Open the highlighted entry
+    Open,
+    ///Open the entry of that index in a menu
+    Entry(usize),
+    ///Validate the typed text or the displayed choice
+    Submit,
+    Erase,
+    Complete,
+    Toggle,
+    Back,
+    Confirm,
+    Cancel,
+    Pause,
+    ///Move the player's paddle, or the right one in a local match
+    Up,
+    Down,
+    ///Move the left paddle of a local match
+    LeftUp,
+    LeftDown,
+    Invite,
+    AddFriend,
+    Requests,
+    Remove,
+    Accept,
+    Decline,
+    Ranking,
+    Mine,
+    Filter,
+    SeekBackward,
+    SeekForward,
+    Faster,
+    Slower,
+    StepBackward,
+    StepForward,
+    Refresh,
+    Create,
+    Rematch,
+}
+
+///A row of the help overlay: keys with the action each one triggers, then what they do
+pub(crate) struct Binding(pub(crate) &'static [(KeyCode, Action)], pub(crate) &'static str);
+
+impl Binding {
+    ///Keys of the row as shown in the help, consecutive digits being written as a range
+    pub(crate) fn keys_label(&self) -> String {
+        if let (Some((KeyCode::Char(first), _)), Some((KeyCode::Char(last), _))) =
+            (self.0.first(), self.0.last())
+            && self.0.len() > 2
+            && first.is_ascii_digit()
+            && *last as usize - *first as usize + 1 == self.0.len()
+        {
+            return format!("{first}-{last}");
+        }
+        let names: Vec<String> = self.0.iter().map(|(key, _)| key_name(*key)).collect();
+        names.join(" ")
+    }
+}
+
+fn key_name(key: KeyCode) -> String {
+    match key {
+        KeyCode::Up => "↑".to_string(),
+        KeyCode::Down => "↓".to_string(),
+        KeyCode::Left => "←".to_string(),
+        KeyCode::Right => "→".to_string(),
+        KeyCode::Esc => "ESC".to_string(),
+        KeyCode::BackTab => "Shift+Tab".to_string(),
+        KeyCode::Delete => "Del".to_string(),
+        KeyCode::PageUp => "PgUp".to_string(),
+        KeyCode::PageDown => "PgDn".to_string(),
+        KeyCode::Char(' ') => "Space".to_string(),
+        KeyCode::Char(c) => c.to_string(),
+        key => key.to_string(),
+    }
+}
+
+///Rows of the help listing keys handled outside of the screens: key, then what it does
+pub(crate) const GLOBAL_BINDINGS: [(&str, &str); 3] = [
+    ("? or F1", "Show or hide this help (F1 only where text is typed)"),
+    ("Ctrl+C", "Same as ESC"),
+    ("Mouse", "Click menu entries and friends, scroll the friends list"),
+];
+
+///Change pages of a list
+const PAGES: Binding = Binding(
+    &[
+        (KeyCode::Left, Action::PreviousPage),
+        (KeyCode::Right, Action::NextPage),
+        (KeyCode::PageUp, Action::PreviousPage),
+        (KeyCode::PageDown, Action::NextPage),
+    ],
+    "Change page",
+);
+const BACK: Binding = Binding(&[(KeyCode::Esc, Action::Back)], "Back");
+
+///Keys handled by each screen, which look up what they do in this table
+pub(crate) fn bindings(screen: CurrentScreen) -> &'static [Binding] {
+    match screen {
+        CurrentScreen::FirstScreen
+        | CurrentScreen::Welcome
+        | CurrentScreen::GameChoice
+        | CurrentScreen::SocialLife => &[
+            Binding(
+                &[
+                    (KeyCode::Up, Action::Previous),
+                    (KeyCode::Down, Action::Next),
+                    (KeyCode::Tab, Action::Next),
+                    (KeyCode::BackTab, Action::Previous),
+                ],
+                "Highlight an entry",
+            ),
+            Binding(
+                &[(KeyCode::Home, Action::First), (KeyCode::End, Action::Last)],
+                "Highlight the first or last entry",
+            ),
+            Binding(&[(KeyCode::Enter, Action::Open)], "Open the highlighted entry"),
+            Binding(
+                &[
+                    (KeyCode::Char('1'), Action::Entry(0)),
+                    (KeyCode::Char('2'), Action::Entry(1)),
+                    (KeyCode::Char('3'), Action::Entry(2)),
+                    (KeyCode::Char('4'), Action::Entry(3)),
+                    (KeyCode::Char('5'), Action::Entry(4)),
+                    (KeyCode::Char('6'), Action::Entry(5)),
+                    (KeyCode::Char('7'), Action::Entry(6)),
+                    (KeyCode::Char('8'), Action::Entry(7)),
+                    (KeyCode::Char('9'), Action::Entry(8)),
+                ],
+                "Open the entry of that number",
+            ),
+            Binding(&[(KeyCode::Esc, Action::Back)], "Back, quit from the main menu"),
+        ],
+        CurrentScreen::SignUp | CurrentScreen::Login => &[
+            Binding(
+                &[
+                    (KeyCode::Up, Action::Previous),
+                    (KeyCode::Down, Action::Next),
+                    (KeyCode::Tab, Action::Next),
+                ],
+                "Change field",
+            ),
+            Binding(&[(KeyCode::Backspace, Action::Erase)], "Erase"),
+            Binding(&[(KeyCode::Enter, Action::Submit)], "Next field, submit on the last one"),
+            BACK,
+        ],
+        //Not redrawn while waiting for a key, the help cannot be shown over them
+        CurrentScreen::CreateGame | CurrentScreen::ErrorScreen => &[],
+        CurrentScreen::StartGame | CurrentScreen::PlayGame => &[
+            Binding(
+                &[(KeyCode::Up, Action::Up), (KeyCode::Down, Action::Down)],
+                "Move your paddle",
+            ),
+            Binding(&[(KeyCode::Char('p'), Action::Pause)], "Pause or resume"),
+            Binding(
+                &[(KeyCode::Esc, Action::Back)],
+                "Leave, forfeiting the game (stop watching when spectating)",
+            ),
+        ],
+        CurrentScreen::EndGame => &[
+            Binding(&[(KeyCode::Char('r'), Action::Rematch)], "Propose or accept a rematch"),
+            Binding(&[(KeyCode::Char('d'), Action::Decline)], "Decline a rematch"),
+            Binding(&[(KeyCode::Enter, Action::Back), (KeyCode::Esc, Action::Back)], "Continue"),
+        ],
+        CurrentScreen::FriendsDisplay => &[
+            Binding(
+                &[(KeyCode::Up, Action::Previous), (KeyCode::Down, Action::Next)],
+                "Select a friend",
+            ),
+            PAGES,
+            Binding(
+                &[(KeyCode::Enter, Action::Open), (KeyCode::Char('p'), Action::Open)],
+                "Open profile",
+            ),
+            Binding(
+                &[(KeyCode::Char('i'), Action::Invite)],
+                "Join the online queue to play the friend",
+            ),
+            Binding(&[(KeyCode::Char('a'), Action::AddFriend)], "Add a friend"),
+            Binding(&[(KeyCode::Char('r'), Action::Requests)], "Friend requests"),
+            Binding(
+                &[(KeyCode::Char('x'), Action::Remove), (KeyCode::Delete, Action::Remove)],
+                "Remove friend",
+            ),
+            BACK,
+        ],
+        CurrentScreen::AddFriend => &[
+            Binding(
+                &[(KeyCode::Up, Action::Previous), (KeyCode::Down, Action::Next)],
+                "Select a suggestion",
+            ),
+            Binding(&[(KeyCode::Tab, Action::Complete)], "Complete with the suggestion"),
+            Binding(&[(KeyCode::Backspace, Action::Erase)], "Erase"),
+            Binding(&[(KeyCode::Enter, Action::Submit)], "Send the friend request"),
+            BACK,
+        ],
+        CurrentScreen::FriendRequests => &[
+            Binding(
+                &[(KeyCode::Up, Action::Previous), (KeyCode::Down, Action::Next)],
+                "Select a request",
+            ),
+            Binding(
+                &[(KeyCode::Enter, Action::Accept), (KeyCode::Char('a'), Action::Accept)],
+                "Accept",
+            ),
+            Binding(&[(KeyCode::Char('d'), Action::Decline)], "Decline"),
+            BACK,
+        ],
+        CurrentScreen::Profile => &[PAGES, BACK],
+        CurrentScreen::Leaderboard => &[
+            Binding(
+                &[(KeyCode::Up, Action::Previous), (KeyCode::Down, Action::Next)],
+                "Select a player",
+            ),
+            PAGES,
+            Binding(&[(KeyCode::Char('s'), Action::Ranking)], "Change ranking"),
+            Binding(&[(KeyCode::Char('m'), Action::Mine)], "Jump to your rank"),
+            Binding(&[(KeyCode::Enter, Action::Open)], "Open profile"),
+            BACK,
+        ],
+        CurrentScreen::LocalHistory => &[
+            Binding(
+                &[(KeyCode::Up, Action::Previous), (KeyCode::Down, Action::Next)],
+                "Select a match",
+            ),
+            PAGES,
+            Binding(&[(KeyCode::Char('f'), Action::Filter)], "Filter matches"),
+            Binding(&[(KeyCode::Enter, Action::Open)], "Replay the match"),
+            BACK,
+        ],
+        CurrentScreen::Replay => &[
+            Binding(&[(KeyCode::Char(' '), Action::Pause)], "Pause or resume"),
+            Binding(
+                &[(KeyCode::Left, Action::SeekBackward), (KeyCode::Right, Action::SeekForward)],
+                "Seek 5 seconds",
+            ),
+            Binding(
+                &[
+                    (KeyCode::Up, Action::Faster),
+                    (KeyCode::Down, Action::Slower),
+                    (KeyCode::Char('+'), Action::Faster),
+                    (KeyCode::Char('-'), Action::Slower),
+                ],
+                "Change speed",
+            ),
+            Binding(
+                &[
+                    (KeyCode::Char(','), Action::StepBackward),
+                    (KeyCode::Char('.'), Action::StepForward),
+                ],
+                "Step one frame while paused",
+            ),
+            BACK,
+        ],
+        CurrentScreen::LiveGames => &[
+            Binding(
+                &[(KeyCode::Up, Action::Previous), (KeyCode::Down, Action::Next)],
+                "Select a game",
+            ),
+            PAGES,
+            Binding(&[(KeyCode::Char('r'), Action::Refresh)], "Refresh"),
+            Binding(&[(KeyCode::Enter, Action::Open)], "Watch the game"),
+            BACK,
+        ],
+        CurrentScreen::Tournaments => &[
+            Binding(
+                &[(KeyCode::Up, Action::Previous), (KeyCode::Down, Action::Next)],
+                "Select a tournament",
+            ),
+            PAGES,
+            Binding(&[(KeyCode::Enter, Action::Open)], "Join or open the bracket"),
+            Binding(&[(KeyCode::Char('c'), Action::Create)], "Create a tournament"),
+            Binding(&[(KeyCode::Char('r'), Action::Refresh)], "Refresh"),
+            BACK,
+        ],
+        CurrentScreen::Bracket => &[
+            Binding(&[(KeyCode::Enter, Action::Open)], "Start your match when ready"),
+            Binding(&[(KeyCode::Esc, Action::Back)], "Back to the tournaments"),
+        ],
+        CurrentScreen::LocalSetup => &[
+            Binding(
+                &[(KeyCode::Enter, Action::Submit)],
+                "Add the player, draw the matches when no name is typed",
+            ),
+            Binding(&[(KeyCode::Tab, Action::Toggle)], "Switch between human and bot"),
+            Binding(
+                &[(KeyCode::Left, Action::Previous), (KeyCode::Right, Action::Next)],
+                "Change format",
+            ),
+            Binding(
+                &[(KeyCode::Backspace, Action::Erase)],
+                "Erase, remove the last player when no name is typed",
+            ),
+            BACK,
+        ],
+        CurrentScreen::LocalStandings => &[
+            Binding(&[(KeyCode::Enter, Action::Open)], "Play the next match"),
+            Binding(&[(KeyCode::Esc, Action::Back)], "Quit the tournament"),
+        ],
+        CurrentScreen::LocalPlay => &[
+            Binding(
+                &[(KeyCode::Char('w'), Action::LeftUp), (KeyCode::Char('s'), Action::LeftDown)],
+                "Move the left paddle",
+            ),
+            Binding(
+                &[(KeyCode::Up, Action::Up), (KeyCode::Down, Action::Down)],
+                "Move the right paddle",
+            ),
+            Binding(&[(KeyCode::Char('p'), Action::Pause)], "Pause or resume"),
+            Binding(&[(KeyCode::Esc, Action::Back)], "Give up the match"),
+        ],
+    }
+}
+
+///Keys of the popup a screen may open, with the title of its section of the help
+pub(crate) fn popup_bindings(screen: CurrentScreen) -> Option<(&'static str, &'static [Binding])> {
+    let popup: (&str, &[Binding]) = match screen {
+        CurrentScreen::StartGame | CurrentScreen::PlayGame => (
+            "When leaving",
+            &[
+                Binding(
+                    &[(KeyCode::Char('y'), Action::Confirm), (KeyCode::Enter, Action::Confirm)],
+                    "Leave the game",
+                ),
+                Binding(
+                    &[(KeyCode::Char('n'), Action::Cancel), (KeyCode::Esc, Action::Cancel)],
+                    "Keep playing",
+                ),
+            ],
+        ),
+        CurrentScreen::FriendsDisplay => (
+            "When removing a friend",
+            &[Binding(
+                &[(KeyCode::Char('y'), Action::Confirm), (KeyCode::Enter, Action::Confirm)],
+                "Remove the friend, any other key keeping them",
+            )],
+        ),
+        CurrentScreen::Tournaments => (
+            "When creating a tournament",
+            &[
+                Binding(
+                    &[(KeyCode::Left, Action::Previous), (KeyCode::Right, Action::Next)],
+                    "Change the number of players",
+                ),
+                Binding(&[(KeyCode::Backspace, Action::Erase)], "Erase"),
+                Binding(&[(KeyCode::Enter, Action::Submit)], "Create the tournament"),
+                Binding(&[(KeyCode::Esc, Action::Cancel)], "Cancel"),
+            ],
+        ),
+        _ => return None,
+    };
+    Some(popup)
+}
+
+///Action of a key in a table of bindings
+fn lookup(bindings: &[Binding], key: KeyCode) -> Option<Action> {
+    bindings
+        .iter()
+        .flat_map(|binding| binding.0)
+        .find(|(bound, _)| *bound == key)
+        .map(|(_, action)| *action)
+}
+
+///What the key does on the screen, None if it is not bound there
+pub(crate) fn action(screen: CurrentScreen, key: KeyCode) -> Option<Action> {
+    lookup(bindings(screen), key)
+}
+
+///What the key does in the popup opened over the screen
+pub(crate) fn popup_action(screen: CurrentScreen, key: KeyCode) -> Option<Action> {
+    lookup(popup_bindings(screen)?.1, key)
+}
+
+///Screens where letters are typed, the help being opened with F1 only
+fn text_entry(screen: CurrentScreen) -> bool {
+    matches!(
+        screen,
+        CurrentScreen::SignUp
+            | CurrentScreen::Login
+            | CurrentScreen::AddFriend
+            | CurrentScreen::LocalSetup
+    )
+}
+
+///Screen the help overlay is displayed over
+///
+/// Shared with the task reading the keys during a game
+#[derive(Clone, Default)]
+pub(crate) struct Help(Arc<Mutex<Option<CurrentScreen>>>);
+
+impl Help {
+    ///Whether the help overlay is displayed over the given screen
+    pub(crate) fn is_open(&self, screen: CurrentScreen) -> bool {
+        self.0.lock().is_ok_and(|help| *help == Some(screen))
+    }
+    ///Hide the help overlay of a screen which is not displayed anymore
+    pub(crate) fn close(&self, screen: CurrentScreen) {
+        if let Ok(mut help) = self.0.lock()
+            && *help == Some(screen)
+        {
+            *help = None;
+        }
+    }
+    ///Read the next terminal event, showing or hiding the help overlay on ? or F1
+    ///
+    /// Clicks and scrolling are turned into key presses where the screen made them clickable
+    ///
+    /// #Parameters
+    /// - screen: screen reading the event
+    ///
+    /// #Return
+    /// None for the keys toggling the overlay and the events read while it is displayed,
+    /// which do not reach the screen
+    pub(crate) fn read_event(&self, screen: CurrentScreen) -> Result<Option<Event>> {
+        let event = mouse::translate(event::read()?);
+        let Ok(mut help) = self.0.lock() else {
+            return Ok(Some(event));
+        };
+        if *help == Some(screen) {
+            let closing = match &event {
+                Event::Key(key_event) => key_event.kind == KeyEventKind::Press,
+                Event::Mouse(mouse_event) => matches!(mouse_event.kind, MouseEventKind::Down(_)),
+                _ => false,
+            };
+            if closing {
+                *help = None;
+            }
+            return Ok(None);
+        }
+        let Event::Key(key_event) = &event else {
+            return Ok(Some(event));
+        };
+        let toggle = key_event.code == KeyCode::F(1)
+            || (key_event.code == KeyCode::Char('?') && !text_entry(screen));
+        if !toggle {
+            return Ok(Some(event));
+        }
+        if key_event.kind == KeyEventKind::Press {
+            *help = Some(screen);
+        }
+        Ok(None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn labels_of_the_keys() {
+        assert_eq!(bindings(CurrentScreen::Welcome)[0].keys_label(), "↑ ↓ Tab Shift+Tab");
+        assert_eq!(bindings(CurrentScreen::Welcome)[3].keys_label(), "1-9");
+        assert_eq!(PAGES.keys_label(), "← → PgUp PgDn");
+        assert_eq!(bindings(CurrentScreen::Replay)[0].keys_label(), "Space");
+    }
+
+    #[test]
+    fn keys_are_bound_once_per_table() {
+        let screens = [
+            CurrentScreen::FirstScreen,
+            CurrentScreen::SignUp,
+            CurrentScreen::PlayGame,
+            CurrentScreen::EndGame,
+            CurrentScreen::FriendsDisplay,
+            CurrentScreen::AddFriend,
+            CurrentScreen::FriendRequests,
+            CurrentScreen::Profile,
+            CurrentScreen::Leaderboard,
+            CurrentScreen::LocalHistory,
+            CurrentScreen::Replay,
+            CurrentScreen::LiveGames,
+            CurrentScreen::Tournaments,
+            CurrentScreen::Bracket,
+            CurrentScreen::LocalSetup,
+            CurrentScreen::LocalStandings,
+            CurrentScreen::LocalPlay,
+        ];
+        for screen in screens {
+            let tables = std::iter::once(bindings(screen))
+                .chain(popup_bindings(screen).map(|(_, popup)| popup));
+            for table in tables {
+                let keys: Vec<KeyCode> =
+                    table.iter().flat_map(|binding| binding.0).map(|(key, _)| *key).collect();
+                for (index, key) in keys.iter().enumerate() {
+                    assert!(!keys[index + 1..].contains(key), "{key:?} bound twice on {screen:?}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn confirmation_of_the_removal_of_a_friend() {
+        let screen = CurrentScreen::FriendsDisplay;
+        assert_eq!(popup_action(screen, KeyCode::Char('y')), Some(Action::Confirm));
+        assert_eq!(popup_action(screen, KeyCode::Enter), Some(Action::Confirm));
+        assert_eq!(popup_action(screen, KeyCode::Char('n')), None);
+        assert_eq!(action(screen, KeyCode::Delete), Some(Action::Remove));
+    }
+}
@@ -0,0 +1,105 @@
+use crate::Context;
+use crate::pager::Pager;
+use crate::profile::Profile;
+use anyhow::{Result, anyhow};
+use std::rc::Rc;
+
+///Criteria used to rank players
+#[derive(Clone, Copy, PartialEq, Default)]
+pub(crate) enum Ranking {
+    #[default]
+    Wins,
+    Rating,
+}
+
+impl Ranking {
+    pub(crate) fn label(&self) -> &'static str {
+        match self {
+            Ranking::Wins => "wins",
+            Ranking::Rating => "rating",
+        }
+    }
+}
+
+pub(crate) struct LeaderboardEntry {
+    pub(crate) profile: Profile,
+    pub(crate) rating: u64,
+}
+
+#[derive(Default)]
+pub(crate) struct Leaderboard {
+    pub(crate) entries: Vec<LeaderboardEntry>,
+    pub(crate) ranking: Ranking,
+    pub(crate) pager: Pager,
+}
+
+impl Leaderboard {
+    ///Fetch the ranking of every player
+    ///
+    /// #Errors
+    /// Returns an error if the server does not send a list of players
+    pub(crate) async fn fetch(context: Rc<Context>) -> Result<Leaderboard> {
+        let apiloc = format!("https://{}/api/user/leaderboard", context.location);
+        let response = context.client.get(apiloc).send().await?;
+        let response: serde_json::Value = response.json().await?;
+        let players = match response.as_array() {
+            Some(players) => players,
+            _ => match response["message"].as_str() {
+                Some(message) => return Err(anyhow!(message.to_string())),
+                _ => return Err(anyhow!("Leaderboard unavailable")),
+            },
+        };
+        let entries = players
+            .iter()
+            .filter_map(|player| {
+                Some(LeaderboardEntry {
+                    profile: Profile::from_value(player, player["id"].as_u64()?)?,
+                    rating: player["elo"]
+                        .as_u64()
+                        .or(player["rating"].as_u64())
+                        .unwrap_or_default(),
+                })
+            })
+            .collect();
+        let mut leaderboard = Leaderboard {
+            entries,
+            ..Default::default()
+        };
+        leaderboard.sort();
+        Ok(leaderboard)
+    }
+    ///Rank players by wins or by rating
+    pub(crate) fn toggle_ranking(&mut self) {
+        self.ranking = match self.ranking {
+            Ranking::Wins => Ranking::Rating,
+            Ranking::Rating => Ranking::Wins,
+        };
+        self.sort();
+    }
+    fn sort(&mut self) {
+        let ranking = self.ranking;
+        self.entries.sort_by_key(|entry| {
+            let score = match ranking {
+                Ranking::Wins => entry.profile.wins,
+                Ranking::Rating => entry.rating,
+            };
+            (std::cmp::Reverse(score), entry.profile.name.to_lowercase())
+        });
+        self.pager.set_len(self.entries.len());
+        self.pager.select(0);
+    }
+    ///Move the cursor on the given user
+    pub(crate) fn jump_to(&mut self, id: u64) {
+        if let Some(position) = self.entries.iter().position(|entry| entry.profile.id == id) {
+            self.pager.select(position);
+        }
+    }
+    pub(crate) fn selected_entry(&self) -> Option<&LeaderboardEntry> {
+        self.entries.get(self.pager.selected)
+    }
+    ///Update leaderboard pages depending on the terminal's size
+    pub(crate) fn update_index(&mut self, terminal: &mut ratatui::DefaultTerminal) {
+        let page_height = terminal.get_frame().area().height.saturating_sub(3) as usize;
+        self.pager.update(self.entries.len(), page_height);
+    }
+}
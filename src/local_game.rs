@@ -0,0 +1,302 @@
+use crate::court::CourtConfig;
+use crate::game::GameStats;
+use crate::keymap::Action;
+use crossterm::event::KeyEventKind;
+use std::time::{Duration, Instant};
+
+///Points needed to win a local game
+pub(crate) const WINNING_SCORE: u8 = 5;
+///Paddle speed, in field units per second
+const PADDLE_SPEED: f32 = 70.0;
+///Fraction of the paddle speed bots move at
+const BOT_SPEED: f32 = 0.7;
+///Ball speed at each serve, in field units per second
+const SERVE_SPEED: f32 = 45.0;
+///Speed gained by the ball at each hit
+const SPEEDUP: f32 = 1.06;
+///Pause before each serve, in seconds
+const SERVE_DELAY: f32 = 1.0;
+///Time a key is considered held after its last press or repeat event
+const KEY_HOLD: Duration = Duration::from_millis(150);
+///Longest game simulated without display before the next point decides it
+const MAX_HEADLESS_STEPS: usize = 60 * 60 * 20;
+
+///A game of Pong simulated in process, using the server's coordinates
+pub(crate) struct Simulation {
+    pub(crate) stats: GameStats,
+    pub(crate) config: CourtConfig,
+    ball_dx: f32,
+    ball_dy: f32,
+    ///Seconds before the ball is served
+    serve_in: f32,
+    ///State of the generator varying serve angles
+    seed: u64,
+}
+
+impl Default for Simulation {
+    fn default() -> Self {
+        let mut simulation = Simulation {
+            stats: GameStats {
+                left_y: 50.0,
+                right_y: 50.0,
+                ..Default::default()
+            },
+            config: CourtConfig::default(),
+            ball_dx: 0.0,
+            ball_dy: 0.0,
+            serve_in: 0.0,
+            seed: std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .map(|duration| duration.as_nanos() as u64)
+                .unwrap_or_default()
+                | 1,
+        };
+        simulation.serve(1.0);
+        simulation
+    }
+}
+
+impl Simulation {
+    ///Put the ball back in the middle, to be sent toward the given side
+    ///
+    /// #Parameters
+    /// - direction: -1.0 toward the left player, 1.0 toward the right one
+    fn serve(&mut self, direction: f32) {
+        self.stats.ball_x = 50.0;
+        self.stats.ball_y = 50.0;
+        self.ball_dx = direction * SERVE_SPEED;
+        self.ball_dy = self.random_angle() * SERVE_SPEED;
+        self.serve_in = SERVE_DELAY;
+    }
+    ///Slope between -0.5 and 0.5, from a xorshift generator
+    fn random_angle(&mut self) -> f32 {
+        self.seed ^= self.seed << 13;
+        self.seed ^= self.seed >> 7;
+        self.seed ^= self.seed << 17;
+        (self.seed % 1000) as f32 / 1000.0 - 0.5
+    }
+    ///Advance the game
+    ///
+    /// #Parameters
+    /// - elapsed: seconds since the last step
+    /// - left, right: paddle moves between -1.0 (up) and 1.0 (down)
+    pub(crate) fn step(&mut self, elapsed: f32, left: f32, right: f32) {
+        if self.winner().is_some() {
+            return;
+        }
+        let half_height = self.config.paddle_height as f32 / 2.0;
+        let bound = |y: f32| y.clamp(half_height, 100.0 - half_height);
+        self.stats.left_y = bound(self.stats.left_y + left * PADDLE_SPEED * elapsed);
+        self.stats.right_y = bound(self.stats.right_y + right * PADDLE_SPEED * elapsed);
+        if self.serve_in > 0.0 {
+            self.serve_in -= elapsed;
+            return;
+        }
+        let (previous_x, previous_y) = (self.stats.ball_x, self.stats.ball_y);
+        self.stats.ball_x += self.ball_dx * elapsed;
+        self.stats.ball_y += self.ball_dy * elapsed;
+        if self.stats.ball_y < 0.0 || self.stats.ball_y > 100.0 {
+            self.stats.ball_y = self.stats.ball_y.clamp(0.0, 100.0);
+            self.ball_dy = -self.ball_dy;
+        }
+        let radius = self.config.ball_radius as f32;
+        let left_x = self.config.left_paddle_x() as f32;
+        let right_x = self.config.right_paddle_x() as f32;
+        let paddle_width = self.config.paddle_width as f32;
+        let (face, back, paddle_y) = match self.ball_dx < 0.0 {
+            true => (left_x + paddle_width, left_x, self.stats.left_y),
+            false => (right_x, right_x + paddle_width, self.stats.right_y),
+        };
+        //Side of the ball facing the paddle, before and after the step
+        let direction = self.ball_dx.signum();
+        let edge = direction * radius;
+        let (before, after) = (previous_x + edge, self.stats.ball_x + edge);
+        //Once fast, the ball moves further than the paddle's width in a step:
+        //it is hit if it reached the face without having been past the back before
+        if (after - face) * direction >= 0.0 && (back - before) * direction >= 0.0 {
+            let ratio = match after != before {
+                true => ((face - before) / (after - before)).clamp(0.0, 1.0),
+                false => 0.0,
+            };
+            let hit_y = previous_y + (self.stats.ball_y - previous_y) * ratio;
+            self.bounce(paddle_y, face - edge, hit_y.clamp(0.0, 100.0));
+        }
+        if self.stats.ball_x < 0.0 {
+            self.stats.player2_score += 1;
+            self.serve(-1.0);
+        } else if self.stats.ball_x > 100.0 {
+            self.stats.player1_score += 1;
+            self.serve(1.0);
+        }
+    }
+    ///Send the ball back if the paddle is on its way, steeper when hit on the edges
+    ///
+    /// #Parameters
+    /// - ball_x, ball_y: position of the ball's center when touching the paddle's face
+    fn bounce(&mut self, paddle_y: f32, ball_x: f32, ball_y: f32) {
+        let offset = (ball_y - paddle_y) / (self.config.paddle_height as f32 / 2.0);
+        if offset.abs() > 1.1 {
+            return;
+        }
+        let speed = self.ball_dx.abs() * SPEEDUP;
+        self.ball_dx = -self.ball_dx.signum() * speed;
+        self.ball_dy = offset * speed * 0.8;
+        self.stats.ball_x = ball_x;
+        self.stats.ball_y = ball_y;
+    }
+    ///Paddle move of a bot, following the ball when it comes its way
+    pub(crate) fn bot_input(&self, left: bool) -> f32 {
+        let (paddle_y, incoming) = match left {
+            true => (self.stats.left_y, self.ball_dx < 0.0),
+            false => (self.stats.right_y, self.ball_dx > 0.0),
+        };
+        let target = match incoming && self.serve_in <= 0.0 {
+            true => self.stats.ball_y,
+            false => 50.0,
+        };
+        match target - paddle_y {
+            diff if diff > 2.0 => BOT_SPEED,
+            diff if diff < -2.0 => -BOT_SPEED,
+            _ => 0.0,
+        }
+    }
+    ///Whether the left player won, once a player reached the winning score
+    pub(crate) fn winner(&self) -> Option<bool> {
+        if self.stats.player1_score >= WINNING_SCORE {
+            Some(true)
+        } else if self.stats.player2_score >= WINNING_SCORE {
+            Some(false)
+        } else {
+            None
+        }
+    }
+    ///Play a game between two bots without displaying it
+    ///
+    /// A game still tied after MAX_HEADLESS_STEPS goes on until the next point,
+    /// then a coin toss decides if neither bot ever misses
+    ///
+    /// #Return
+    /// The final score, left player first, never a tie
+    pub(crate) fn play_bots() -> (u8, u8) {
+        let mut simulation = Simulation::default();
+        for step in 0..MAX_HEADLESS_STEPS * 2 {
+            let tied = simulation.stats.player1_score == simulation.stats.player2_score;
+            if simulation.winner().is_some() || (step >= MAX_HEADLESS_STEPS && !tied) {
+                break;
+            }
+            let (left, right) = (simulation.bot_input(true), simulation.bot_input(false));
+            simulation.step(1.0 / 60.0, left, right);
+        }
+        if simulation.stats.player1_score == simulation.stats.player2_score {
+            match simulation.random_angle() < 0.0 {
+                true => simulation.stats.player1_score += 1,
+                false => simulation.stats.player2_score += 1,
+            }
+        }
+        (simulation.stats.player1_score, simulation.stats.player2_score)
+    }
+}
+
+///Keys held by the players sharing the keyboard
+///
+/// Terminals seldom report key releases, so a key is held until shortly after its last repeat
+#[derive(Default)]
+pub(crate) struct SharedKeyboard {
+    left_up: Option<Instant>,
+    left_down: Option<Instant>,
+    right_up: Option<Instant>,
+    right_down: Option<Instant>,
+}
+
+impl SharedKeyboard {
+    ///Update held keys: W/S for the left player, ↑/↓ for the right one
+    ///
+    /// #Parameters
+    /// - action: paddle move bound to the key
+    /// - kind: press, repeat or release of the key
+    /// - solo: side of the human playing against a bot, Some(true) for the left one;
+    ///   both sets of keys then move the human's paddle
+    pub(crate) fn handle_key(&mut self, action: Action, kind: KeyEventKind, solo: Option<bool>) {
+        let slot = match (action, solo) {
+            (Action::LeftUp | Action::Up, Some(true)) => &mut self.left_up,
+            (Action::LeftDown | Action::Down, Some(true)) => &mut self.left_down,
+            (Action::LeftUp | Action::Up, Some(false)) => &mut self.right_up,
+            (Action::LeftDown | Action::Down, Some(false)) => &mut self.right_down,
+            (Action::LeftUp, None) => &mut self.left_up,
+            (Action::LeftDown, None) => &mut self.left_down,
+            (Action::Up, None) => &mut self.right_up,
+            (Action::Down, None) => &mut self.right_down,
+            _ => return,
+        };
+        *slot = match kind {
+            KeyEventKind::Release => None,
+            _ => Some(Instant::now()),
+        };
+    }
+    ///Paddle moves of the left and right players
+    pub(crate) fn inputs(&self) -> (f32, f32) {
+        let held = |key: Option<Instant>| key.is_some_and(|at| at.elapsed() < KEY_HOLD);
+        let direction = |up: Option<Instant>, down: Option<Instant>| match (held(up), held(down)) {
+            (true, false) => -1.0,
+            (false, true) => 1.0,
+            _ => 0.0,
+        };
+        (
+            direction(self.left_up, self.left_down),
+            direction(self.right_up, self.right_down),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    ///Ball served from x toward the left paddle, moving by dx per second
+    fn ball_toward_left(x: f32, dx: f32, paddle_y: f32) -> Simulation {
+        let simulation = Simulation::default();
+        Simulation {
+            stats: GameStats {
+                ball_x: x,
+                ball_y: 50.0,
+                left_y: paddle_y,
+                ..simulation.stats
+            },
+            ball_dx: dx,
+            ball_dy: 0.0,
+            serve_in: 0.0,
+            ..simulation
+        }
+    }
+
+    #[test]
+    fn fast_ball_does_not_go_through_the_paddle() {
+        let mut simulation = ball_toward_left(10.0, -600.0, 50.0);
+        simulation.step(1.0 / 60.0, 0.0, 0.0);
+        assert!(simulation.ball_dx > 0.0);
+        assert_eq!(simulation.stats.ball_x, 4.0);
+        assert_eq!(simulation.stats.player2_score, 0);
+    }
+
+    #[test]
+    fn fast_ball_passes_an_absent_paddle() {
+        let mut simulation = ball_toward_left(10.0, -660.0, 90.0);
+        simulation.step(1.0 / 60.0, 0.0, 0.0);
+        assert_eq!(simulation.stats.player2_score, 1);
+    }
+
+    #[test]
+    fn ball_behind_the_paddle_is_not_sent_back() {
+        let mut simulation = ball_toward_left(1.2, -60.0, 50.0);
+        simulation.step(1.0 / 60.0, 0.0, 0.0);
+        assert!(simulation.ball_dx < 0.0);
+    }
+
+    #[test]
+    fn bot_games_have_a_winner() {
+        for _ in 0..4 {
+            let (left, right) = Simulation::play_bots();
+            assert_ne!(left, right);
+        }
+    }
+}
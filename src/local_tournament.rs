@@ -0,0 +1,377 @@
+use crate::local_game::{SharedKeyboard, Simulation};
+use std::time::Instant;
+
+///Longest name of a local player
+pub(crate) const NAME_MAX_LEN: usize = 16;
+
+#[derive(Clone)]
+pub(crate) struct LocalPlayer {
+    pub(crate) name: String,
+    ///Played by the computer
+    pub(crate) bot: bool,
+}
+
+#[derive(Clone, Copy, PartialEq, Default)]
+pub(crate) enum Format {
+    #[default]
+    SingleElimination,
+    RoundRobin,
+}
+
+impl Format {
+    pub(crate) fn label(&self) -> &'static str {
+        match self {
+            Format::SingleElimination => "single elimination",
+            Format::RoundRobin => "round robin",
+        }
+    }
+    pub(crate) fn toggle(&self) -> Format {
+        match self {
+            Format::SingleElimination => Format::RoundRobin,
+            Format::RoundRobin => Format::SingleElimination,
+        }
+    }
+}
+
+///A match of the local tournament, between indexes of players
+pub(crate) struct Fixture {
+    pub(crate) round: usize,
+    pub(crate) left: usize,
+    ///None when the left player has a bye
+    pub(crate) right: Option<usize>,
+    ///Final score, left player first
+    pub(crate) result: Option<(u8, u8)>,
+}
+
+impl Fixture {
+    pub(crate) fn done(&self) -> bool {
+        self.result.is_some() || self.right.is_none()
+    }
+    pub(crate) fn winner(&self) -> Option<usize> {
+        match (self.right, self.result) {
+            (None, _) => Some(self.left),
+            (Some(right), Some((left_score, right_score))) => match left_score > right_score {
+                true => Some(self.left),
+                false => Some(right),
+            },
+            _ => None,
+        }
+    }
+}
+
+///Results of a player over the tournament
+#[derive(Default)]
+pub(crate) struct Standing {
+    pub(crate) player: usize,
+    pub(crate) played: u64,
+    pub(crate) wins: u64,
+    pub(crate) scored: u64,
+    pub(crate) conceded: u64,
+}
+
+pub(crate) struct LocalTournament {
+    pub(crate) players: Vec<LocalPlayer>,
+    pub(crate) format: Format,
+    pub(crate) fixtures: Vec<Fixture>,
+}
+
+impl LocalTournament {
+    ///Draw the first round of a single elimination tournament, or every round of a round robin
+    pub(crate) fn new(players: Vec<LocalPlayer>, format: Format) -> LocalTournament {
+        let fixtures = match format {
+            Format::SingleElimination => {
+                let entrants: Vec<usize> = (0..players.len()).collect();
+                pair_round(&entrants, 0)
+            }
+            Format::RoundRobin => round_robin(players.len()),
+        };
+        LocalTournament {
+            players,
+            format,
+            fixtures,
+        }
+    }
+    ///Index of the next fixture to play
+    pub(crate) fn next_fixture(&self) -> Option<usize> {
+        self.fixtures.iter().position(|fixture| !fixture.done())
+    }
+    ///Record the result of a fixture, drawing the next round when the current one is over
+    pub(crate) fn record(&mut self, index: usize, score: (u8, u8)) {
+        self.fixtures[index].result = Some(score);
+        if self.format != Format::SingleElimination || self.next_fixture().is_some() {
+            return;
+        }
+        let round = self.fixtures[index].round;
+        let winners: Vec<usize> = self
+            .fixtures
+            .iter()
+            .filter(|fixture| fixture.round == round)
+            .filter_map(Fixture::winner)
+            .collect();
+        if winners.len() > 1 {
+            let next_round = pair_round(&winners, round + 1);
+            self.fixtures.extend(next_round);
+        }
+    }
+    ///Play every upcoming fixture between two bots
+    pub(crate) fn play_bot_fixtures(&mut self) {
+        while let Some(index) = self.next_fixture() {
+            let fixture = &self.fixtures[index];
+            let bots = self.players[fixture.left].bot
+                && fixture.right.is_some_and(|right| self.players[right].bot);
+            if !bots {
+                break;
+            }
+            self.record(index, Simulation::play_bots());
+        }
+    }
+    ///Winner of the tournament, once every fixture is played
+    pub(crate) fn champion(&self) -> Option<usize> {
+        if self.next_fixture().is_some() {
+            return None;
+        }
+        match self.format {
+            Format::SingleElimination => self.fixtures.last().and_then(Fixture::winner),
+            Format::RoundRobin => self.standings().first().map(|standing| standing.player),
+        }
+    }
+    ///Players ranked by wins, then point difference, then points scored
+    pub(crate) fn standings(&self) -> Vec<Standing> {
+        let mut standings: Vec<Standing> = (0..self.players.len())
+            .map(|player| Standing {
+                player,
+                ..Default::default()
+            })
+            .collect();
+        for fixture in &self.fixtures {
+            let (Some(right), Some((left_score, right_score))) = (fixture.right, fixture.result)
+            else {
+                continue;
+            };
+            for (player, scored, conceded) in [
+                (fixture.left, left_score, right_score),
+                (right, right_score, left_score),
+            ] {
+                let standing = &mut standings[player];
+                standing.played += 1;
+                standing.scored += scored as u64;
+                standing.conceded += conceded as u64;
+                if scored > conceded {
+                    standing.wins += 1;
+                }
+            }
+        }
+        standings.sort_by_key(|standing| {
+            (
+                std::cmp::Reverse(standing.wins),
+                std::cmp::Reverse(standing.scored as i64 - standing.conceded as i64),
+                std::cmp::Reverse(standing.scored),
+            )
+        });
+        standings
+    }
+}
+
+///Pair players of a single elimination round, the best seeds getting the byes
+fn pair_round(entrants: &[usize], round: usize) -> Vec<Fixture> {
+    let size = entrants.len().next_power_of_two();
+    (0..size / 2)
+        .map(|slot| Fixture {
+            round,
+            left: entrants[slot],
+            right: entrants.get(size - 1 - slot).copied(),
+            result: None,
+        })
+        .collect()
+}
+
+///Every pairing of a round robin, by rounds, using the circle method
+fn round_robin(count: usize) -> Vec<Fixture> {
+    let mut seats: Vec<Option<usize>> = (0..count).map(Some).collect();
+    if count % 2 == 1 {
+        seats.push(None);
+    }
+    let size = seats.len();
+    let mut fixtures = vec![];
+    for round in 0..size.saturating_sub(1) {
+        for slot in 0..size / 2 {
+            if let (Some(left), Some(right)) = (seats[slot], seats[size - 1 - slot]) {
+                fixtures.push(Fixture {
+                    round,
+                    left,
+                    right: Some(right),
+                    result: None,
+                });
+            }
+        }
+        seats[1..].rotate_right(1);
+    }
+    fixtures
+}
+
+///Names typed on the Local tournament setup screen
+#[derive(Default)]
+pub(crate) struct LocalSetup {
+    pub(crate) players: Vec<LocalPlayer>,
+    pub(crate) name: String,
+    ///Whether the next added player is a bot
+    pub(crate) bot: bool,
+    pub(crate) format: Format,
+}
+
+impl LocalSetup {
+    ///Add the typed player, naming bots when no name is given
+    pub(crate) fn add_player(&mut self) {
+        let name = match self.name.trim() {
+            "" if self.bot => format!("Bot {}", self.players.iter().filter(|p| p.bot).count() + 1),
+            "" => return,
+            name => name.to_string(),
+        };
+        self.players.push(LocalPlayer {
+            name,
+            bot: self.bot,
+        });
+        self.name.clear();
+    }
+}
+
+///A fixture being played on screen
+pub(crate) struct LocalMatchInPlay {
+    pub(crate) fixture: usize,
+    pub(crate) simulation: Simulation,
+    pub(crate) keyboard: SharedKeyboard,
+    pub(crate) paused: bool,
+    last_step: Instant,
+}
+
+impl LocalMatchInPlay {
+    pub(crate) fn new(fixture: usize) -> LocalMatchInPlay {
+        LocalMatchInPlay {
+            fixture,
+            simulation: Simulation::default(),
+            keyboard: SharedKeyboard::default(),
+            paused: false,
+            last_step: Instant::now(),
+        }
+    }
+    ///Freeze or resume the game, the paused time not being simulated
+    pub(crate) fn toggle_pause(&mut self) {
+        self.paused = !self.paused;
+        self.last_step = Instant::now();
+    }
+    ///Advance the game by the time elapsed since the last step
+    ///
+    /// #Parameters
+    /// - bots: whether the left and right players are bots
+    pub(crate) fn update(&mut self, bots: (bool, bool)) {
+        if self.paused {
+            return;
+        }
+        let elapsed = self.last_step.elapsed().as_secs_f32().min(0.1);
+        self.last_step = Instant::now();
+        let (mut left, mut right) = self.keyboard.inputs();
+        if bots.0 {
+            left = self.simulation.bot_input(true);
+        }
+        if bots.1 {
+            right = self.simulation.bot_input(false);
+        }
+        self.simulation.step(elapsed, left, right);
+    }
+}
+
+///State of the local tournament screens
+#[derive(Default)]
+pub(crate) struct LocalOrganizer {
+    pub(crate) setup: LocalSetup,
+    pub(crate) tournament: Option<LocalTournament>,
+    pub(crate) playing: Option<LocalMatchInPlay>,
+    ///Summary of the last match played
+    pub(crate) last_result: Option<String>,
+}
+
+impl LocalOrganizer {
+    ///Draw the tournament from the players entered
+    pub(crate) fn start(&mut self) {
+        let mut tournament =
+            LocalTournament::new(self.setup.players.clone(), self.setup.format);
+        tournament.play_bot_fixtures();
+        self.tournament = Some(tournament);
+        self.last_result = None;
+    }
+    ///Start the next fixture, if any
+    pub(crate) fn play_next(&mut self) {
+        if let Some(index) = self.tournament.as_ref().and_then(LocalTournament::next_fixture) {
+            self.playing = Some(LocalMatchInPlay::new(index));
+        }
+    }
+    ///Players of the fixture being played
+    pub(crate) fn current_players(&self) -> Option<(&LocalPlayer, &LocalPlayer)> {
+        let tournament = self.tournament.as_ref()?;
+        let fixture = &tournament.fixtures[self.playing.as_ref()?.fixture];
+        Some((
+            &tournament.players[fixture.left],
+            &tournament.players[fixture.right?],
+        ))
+    }
+    ///Advance the match being played, recording it once over
+    ///
+    /// #Return
+    /// Whether the match is over
+    pub(crate) fn update(&mut self) -> bool {
+        let Some((left, right)) = self
+            .current_players()
+            .map(|(left, right)| (left.clone(), right.clone()))
+        else {
+            self.playing = None;
+            return true;
+        };
+        let (Some(playing), Some(tournament)) = (&mut self.playing, &mut self.tournament) else {
+            return true;
+        };
+        playing.update((left.bot, right.bot));
+        if playing.simulation.winner().is_none() {
+            return false;
+        }
+        let stats = playing.simulation.stats;
+        tournament.record(playing.fixture, (stats.player1_score, stats.player2_score));
+        tournament.play_bot_fixtures();
+        self.last_result = Some(format!(
+            "{} {} - {} {}",
+            left.name, stats.player1_score, stats.player2_score, right.name
+        ));
+        self.playing = None;
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pairs(fixtures: &[Fixture]) -> Vec<(usize, Option<usize>)> {
+        fixtures.iter().map(|fixture| (fixture.left, fixture.right)).collect()
+    }
+
+    #[test]
+    fn full_round() {
+        let fixtures = pair_round(&[0, 1, 2, 3], 0);
+        assert_eq!(pairs(&fixtures), [(0, Some(3)), (1, Some(2))]);
+        assert_eq!(pairs(&pair_round(&[0, 1], 0)), [(0, Some(1))]);
+    }
+
+    #[test]
+    fn best_seeds_get_the_byes() {
+        let fixtures = pair_round(&[0, 1, 2, 3, 4], 0);
+        assert_eq!(pairs(&fixtures), [(0, None), (1, None), (2, None), (3, Some(4))]);
+        assert!(fixtures[..3].iter().all(Fixture::done));
+        assert!(!fixtures[3].done());
+    }
+
+    #[test]
+    fn later_rounds_pair_the_winners() {
+        let fixtures = pair_round(&[4, 1, 6], 2);
+        assert_eq!(pairs(&fixtures), [(4, None), (1, Some(6))]);
+        assert!(fixtures.iter().all(|fixture| fixture.round == 2));
+    }
+}
@@ -1,7 +1,9 @@
 use crate::Context;
+use crate::friends::Presence;
 use crate::game::WsStream;
 use anyhow::{Result, anyhow};
-use futures_util::StreamExt;
+use crate::rematch::RematchAction;
+use futures_util::{SinkExt, StreamExt};
 use std::collections::HashMap;
 use std::rc::Rc;
 use tokio::sync::mpsc;
@@ -16,6 +18,20 @@ pub(crate) enum Field {
     Totp,
 }
 
+///Ends of the channels connecting the client to the chat websocket
+pub(crate) struct ChatChannels {
+    ///Game found notifications, containing a gameId
+    pub(crate) games: mpsc::Receiver<serde_json::Value>,
+    ///Status changes of other users
+    pub(crate) presence: mpsc::Receiver<(u64, Presence)>,
+    ///Rematch messages of other users, with the id of their sender
+    pub(crate) rematch: mpsc::Receiver<(u64, RematchAction)>,
+    ///Messages to send through the websocket
+    pub(crate) outgoing: mpsc::Sender<serde_json::Value>,
+}
+
+pub(crate) type Credentials = (String, u64, ChatChannels);
+
 #[derive(Default)]
 pub(crate) struct Auth {
     pub(crate) token: String,
@@ -27,6 +43,9 @@ pub(crate) struct Auth {
     pub(crate) id: u64,
     pub(crate) blink: bool,
     pub(crate) receiver: Option<mpsc::Receiver<serde_json::Value>>,
+    pub(crate) presence: Option<mpsc::Receiver<(u64, Presence)>>,
+    pub(crate) rematch: Option<mpsc::Receiver<(u64, RematchAction)>>,
+    pub(crate) chat: Option<mpsc::Sender<serde_json::Value>>,
 }
 
 impl Auth {
@@ -125,20 +144,20 @@ impl Auth {
             self.totp.to_string(),
         )
     }
-    pub(crate) fn set_credentials(
-        &mut self,
-        credentials: (String, u64, mpsc::Receiver<serde_json::Value>),
-    ) {
+    pub(crate) fn set_credentials(&mut self, credentials: Credentials) {
         self.token = credentials.0;
         self.id = credentials.1;
-        self.receiver = Some(credentials.2);
+        self.receiver = Some(credentials.2.games);
+        self.presence = Some(credentials.2.presence);
+        self.rematch = Some(credentials.2.rematch);
+        self.chat = Some(credentials.2.outgoing);
     }
 }
 
 pub(crate) async fn signup(
     context: Rc<Context>,
     signup_infos: (String, String, String),
-) -> Result<(String, u64, mpsc::Receiver<serde_json::Value>)> {
+) -> Result<Credentials> {
     let apiloc = format!("https://{}/api/user/create", context.location);
     let mut body: HashMap<&str, &str> = HashMap::new();
     body.insert("username", &signup_infos.0);
@@ -164,7 +183,7 @@ pub(crate) async fn signup(
 pub(crate) async fn login(
     context: Rc<Context>,
     login_infos: (String, String, String),
-) -> Result<(String, u64, mpsc::Receiver<serde_json::Value>)> {
+) -> Result<Credentials> {
     let apiloc = format!("https://{}/api/user/login", context.location);
     let mut body: HashMap<&str, &str> = HashMap::new();
     body.insert("email", &login_infos.0);
@@ -193,7 +212,7 @@ pub(crate) async fn login(
 pub(crate) async fn get_id_and_launch_chat(
     context: Rc<Context>,
     token: String,
-) -> Result<(u64, mpsc::Receiver<serde_json::Value>)> {
+) -> Result<(u64, ChatChannels)> {
     let apiloc = format!("https://{}/api/user/get_profile_token", context.location);
     let mut body = HashMap::new();
     body.insert("token", &token);
@@ -215,7 +234,7 @@ pub(crate) async fn get_id_and_launch_chat(
 
 pub(crate) async fn create_guest_session(
     context: Rc<Context>,
-) -> Result<(String, u64, mpsc::Receiver<serde_json::Value>)> {
+) -> Result<Credentials> {
     let apiloc = format!("https://{}/api/user/create_guest", context.location);
     let res = context.client.post(apiloc).send().await?;
     let body: serde_json::Value = res.json().await?;
@@ -229,7 +248,7 @@ pub(crate) async fn create_guest_session(
     }
 }
 
-async fn enter_chat_room(location: &String, token: &String) -> Result<mpsc::Receiver<serde_json::Value>> {
+async fn enter_chat_room(location: &String, token: &String) -> Result<ChatChannels> {
     let connector = Connector::NativeTls(
         native_tls::TlsConnector::builder()
             .danger_accept_invalid_certs(true)
@@ -242,16 +261,55 @@ async fn enter_chat_room(location: &String, token: &String) -> Result<mpsc::Rece
         mpsc::Sender<serde_json::Value>,
         mpsc::Receiver<serde_json::Value>,
     ) = mpsc::channel(1024);
+    let (presence_sender, presence_receiver) = mpsc::channel(1024);
+    let (rematch_sender, rematch_receiver) = mpsc::channel(16);
+    let (outgoing_sender, outgoing_receiver) = mpsc::channel(16);
     tokio::spawn(async move {
-        if let Err(e) = chat(ws_stream, sender).await {
+        let senders = ChatSenders {
+            games: sender,
+            presence: presence_sender,
+            rematch: rematch_sender,
+        };
+        if let Err(e) = chat(ws_stream, senders, outgoing_receiver).await {
             eprintln!("Error: {e}");
         }
     });
-    Ok(receiver)
+    Ok(ChatChannels {
+        games: receiver,
+        presence: presence_receiver,
+        rematch: rematch_receiver,
+        outgoing: outgoing_sender,
+    })
+}
+
+///Sending ends of the messages dispatched by the chat websocket
+struct ChatSenders {
+    games: mpsc::Sender<serde_json::Value>,
+    presence: mpsc::Sender<(u64, Presence)>,
+    rematch: mpsc::Sender<(u64, RematchAction)>,
 }
 
-async fn chat(mut ws_stream: WsStream, sender: mpsc::Sender<serde_json::Value>) -> Result<()> {
-    while let Some(msg) = ws_stream.next().await {
+///Dispatch chat messages to the client and send the client's ones
+///
+/// #Parameters:
+/// - senders: receive game found notifications, status changes and rematch messages
+/// - outgoing: messages to send to the server
+async fn chat(
+    mut ws_stream: WsStream,
+    senders: ChatSenders,
+    mut outgoing: mpsc::Receiver<serde_json::Value>,
+) -> Result<()> {
+    loop {
+        let msg = tokio::select! {
+            msg = ws_stream.next() => match msg {
+                Some(msg) => msg,
+                _ => break,
+            },
+            Some(message) = outgoing.recv() => {
+                ws_stream.send(Message::Text(message.to_string().into())).await?;
+                continue;
+            }
+        };
         let last_message = match msg {
             Ok(Message::Text(result)) => result,
             _ => {
@@ -259,8 +317,25 @@ async fn chat(mut ws_stream: WsStream, sender: mpsc::Sender<serde_json::Value>)
             }
         };
         let message: serde_json::Value = serde_json::from_str(last_message.as_str())?;
+        if message["type"].as_str() == Some("status")
+            && let Some(user_id) = message["userId"].as_u64()
+            && let Some(presence) = Presence::from_value(&message["status"])
+        {
+            //Presence is only read on the social screens: updates are dropped when it is full
+            //rather than blocking the game notifications behind them
+            let _ = senders.presence.try_send((user_id, presence));
+            continue;
+        }
+        if message["type"].as_str() == Some("rematch")
+            && let Some(from) = message["from"].as_u64()
+            && let Some(action) = RematchAction::from_value(&message["action"])
+        {
+            //Only read on the end-game screen, which clears what arrived before it
+            let _ = senders.rematch.try_send((from, action));
+            continue;
+        }
         match message["gameId"].as_str() {
-            Some(_) => sender.send(message).await?,
+            Some(_) => senders.games.send(message).await?,
             _ => {
                 continue;
             }
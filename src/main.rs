@@ -1,24 +1,54 @@
+mod analytics;
+mod cast;
 mod context;
+mod court;
+mod directory;
 mod friends;
 mod game;
 mod game_demo;
+mod history;
 mod infos;
 mod infos_events;
+mod keymap;
+mod leaderboard;
+mod local_game;
+mod local_tournament;
 mod login;
+mod menu;
+mod mouse;
+mod outcome;
+mod pager;
+mod pause;
+mod profile;
+mod rematch;
+mod replay;
+mod router;
 mod screen_displays;
+mod spectate;
+mod theme;
+mod tournament;
 mod utils;
 
 use anyhow::{Result, anyhow};
+use cast::CastWriter;
 use context::Context;
 use friends::Friends;
 use infos::Infos;
 use login::Auth;
-use std::cell::{Cell, RefCell};
+use router::Router;
+use std::cell::RefCell;
 use std::rc::Rc;
-use utils::{CurrentScreen, LOGO, get_location};
+use utils::{CurrentScreen, LOGO, LOGO_HEIGHT, get_location};
 
 #[tokio::main]
 async fn main() -> Result<()> {
+    theme::load_theme()?;
+    if std::env::args().nth(1).as_deref() == Some("history") {
+        return history::print_history(std::env::args().skip(2));
+    }
+    if std::env::args().nth(1).as_deref() == Some("export") {
+        return cast::export_replay(std::env::args().skip(2));
+    }
     let location = match get_location() {
         Ok(result) => result,
         Err(e) => {
@@ -27,11 +57,29 @@ async fn main() -> Result<()> {
     };
     let context = Rc::new(Context::new(location.clone()));
     let auth = Rc::new(RefCell::new(Auth::default()));
-    let screen = Rc::new(Cell::new(CurrentScreen::default()));
+    let screen = Rc::new(Router::new(match location.as_str() {
+        "local" => CurrentScreen::LocalSetup,
+        _ => CurrentScreen::default(),
+    }));
     let friends = Friends::new(context.clone(), auth.clone(), screen.clone());
+    let cast = match cast::session_path(std::env::args().skip(2))? {
+        Some(path) => {
+            let (width, height) = crossterm::terminal::size()?;
+            Some(CastWriter::create(&path, width, height, "Pong session")?)
+        }
+        _ => None,
+    };
     let mut terminal = ratatui::init();
-    let game_main = Infos::new(context, auth, screen, friends);
+    mouse::disable_mouse_on_panic();
+    if let Err(e) = mouse::enable_mouse() {
+        mouse::disable_mouse();
+        ratatui::restore();
+        return Err(e);
+    }
+    let mut game_main = Infos::new(context, auth, screen, friends);
+    game_main.cast = cast;
     let app_result = game_main.run(&mut terminal).await;
+    mouse::disable_mouse();
     ratatui::restore();
     app_result
 }
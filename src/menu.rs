@@ -0,0 +1,134 @@
+use crate::CurrentScreen;
+use crate::keymap::{self, Action};
+use crate::mouse;
+use crossterm::event::{Event, KeyEventKind};
+
+///Entry of a menu screen
+#[derive(Clone, Copy, PartialEq)]
+pub(crate) enum MenuAction {
+    SignUp,
+    Login,
+    Guest,
+    LocalTournament,
+    Game,
+    SocialLife,
+    Profile,
+    Online,
+    Watch,
+    History,
+    Tournaments,
+    Friends,
+    Leaderboard,
+    Back,
+    Quit,
+}
+
+impl MenuAction {
+    pub(crate) fn label(&self) -> &'static str {
+        match self {
+            MenuAction::SignUp => "Sign up",
+            MenuAction::Login => "Login",
+            MenuAction::Guest => "Sign in as guest",
+            MenuAction::LocalTournament => "Local tournament",
+            MenuAction::Game => "Game",
+            MenuAction::SocialLife => "Social life",
+            MenuAction::Profile => "Profile",
+            MenuAction::Online => "Play online",
+            MenuAction::Watch => "Watch games",
+            MenuAction::History => "My history",
+            MenuAction::Tournaments => "Tournaments",
+            MenuAction::Friends => "Your friends",
+            MenuAction::Leaderboard => "Leaderboard",
+            MenuAction::Back => "Back",
+            MenuAction::Quit => "Quit",
+        }
+    }
+}
+
+///Entries of each menu screen, none for the other screens
+pub(crate) fn entries(screen: CurrentScreen) -> &'static [MenuAction] {
+    match screen {
+        CurrentScreen::FirstScreen => &[
+            MenuAction::SignUp,
+            MenuAction::Login,
+            MenuAction::Guest,
+            MenuAction::LocalTournament,
+            MenuAction::Quit,
+        ],
+        CurrentScreen::Welcome => &[
+            MenuAction::Game,
+            MenuAction::SocialLife,
+            MenuAction::Profile,
+            MenuAction::Quit,
+        ],
+        CurrentScreen::GameChoice => &[
+            MenuAction::Online,
+            MenuAction::Watch,
+            MenuAction::History,
+            MenuAction::Tournaments,
+            MenuAction::Back,
+        ],
+        CurrentScreen::SocialLife => &[
+            MenuAction::Friends,
+            MenuAction::Leaderboard,
+            MenuAction::Back,
+        ],
+        _ => &[],
+    }
+}
+
+///Highlighted entry of the displayed menu
+#[derive(Default)]
+pub(crate) struct Menu {
+    screen: CurrentScreen,
+    selected: usize,
+}
+
+impl Menu {
+    ///Highlighted entry of the menu of the given screen, the first one when just opened
+    pub(crate) fn selected(&self, screen: CurrentScreen) -> usize {
+        match self.screen == screen {
+            true => self.selected,
+            false => 0,
+        }
+    }
+    ///Move the highlight with the arrows, Tab or the wheel, activate an entry with Enter or a click
+    ///
+    /// #Return
+    /// The activated entry, if any
+    pub(crate) fn handle_event(
+        &mut self,
+        screen: CurrentScreen,
+        event: &Event,
+    ) -> Option<MenuAction> {
+        if self.screen != screen {
+            self.screen = screen;
+            self.selected = 0;
+        }
+        let entries = entries(screen);
+        let len = entries.len().max(1);
+        if let Some(row) = mouse::clicked_row(event) {
+            self.selected = row.min(len - 1);
+            return entries.get(row).copied();
+        }
+        let Event::Key(key_event) = event else {
+            return None;
+        };
+        if key_event.kind != KeyEventKind::Press {
+            return None;
+        }
+        match keymap::action(screen, key_event.code) {
+            Some(Action::Previous) => self.selected = (self.selected + len - 1) % len,
+            Some(Action::Next) => self.selected = (self.selected + 1) % len,
+            Some(Action::First) => self.selected = 0,
+            Some(Action::Last) => self.selected = len - 1,
+            Some(Action::Open) => return entries.get(self.selected).copied(),
+            Some(Action::Entry(index)) if index < entries.len() => {
+                self.selected = index;
+                return Some(entries[index]);
+            }
+            _ => {}
+        }
+        None
+    }
+}
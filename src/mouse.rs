@@ -0,0 +1,115 @@
+use anyhow::Result;
+use crossterm::event::{
+    DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyEvent, KeyModifiers, MouseButton,
+    MouseEventKind,
+};
+use ratatui::{
+    layout::{Position, Rect},
+    text::Line,
+};
+use std::sync::Mutex;
+
+///What clicking or scrolling over an area of the screen does
+#[derive(Clone, Copy)]
+pub(crate) enum Target {
+    ///Clicking presses the key
+    Key(KeyCode),
+    ///Clicking selects the row of a list, scrolling moves the selection
+    Row(usize),
+}
+
+///Clickable areas of the last drawn frame
+static TARGETS: Mutex<Vec<(Rect, Target)>> = Mutex::new(Vec::new());
+
+///Capture the mouse, unless PONG_MOUSE is off to keep the terminal's text selection
+///
+/// #Errors
+/// Returns an error if the terminal could not be written to
+pub(crate) fn enable_mouse() -> Result<()> {
+    if std::env::var("PONG_MOUSE").is_ok_and(|mouse| mouse == "off") {
+        return Ok(());
+    }
+    crossterm::execute!(std::io::stdout(), EnableMouseCapture)?;
+    Ok(())
+}
+
+pub(crate) fn disable_mouse() {
+    let _ = crossterm::execute!(std::io::stdout(), DisableMouseCapture);
+}
+
+///Release the mouse when the client panics, before the previously installed hook
+/// restores the rest of the terminal
+pub(crate) fn disable_mouse_on_panic() {
+    let hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        disable_mouse();
+        hook(info);
+    }));
+}
+
+///Forget the clickable areas, before drawing a new frame
+pub(crate) fn clear_targets() {
+    if let Ok(mut targets) = TARGETS.lock() {
+        targets.clear();
+    }
+}
+
+pub(crate) fn add_target(area: Rect, target: Target) {
+    if let Ok(mut targets) = TARGETS.lock() {
+        targets.push((area, target));
+    }
+}
+
+///Make each span of a centered block title clickable
+///
+/// #Parameters
+/// - line: title, drawn on the top or bottom border
+/// - area: area of the bordered block
+/// - y: row of the title
+/// - keys: key pressed by clicking each span, None for spans doing nothing
+pub(crate) fn add_title_targets(line: &Line, area: Rect, y: u16, keys: &[Option<KeyCode>]) {
+    let inner_width = area.width.saturating_sub(2);
+    let mut x = area.x + 1 + inner_width.saturating_sub(line.width() as u16) / 2;
+    for (span, key) in line.spans.iter().zip(keys) {
+        let width = span.width() as u16;
+        if let Some(key) = key {
+            add_target(Rect::new(x, y, width, 1).intersection(area), Target::Key(*key));
+        }
+        x += width;
+    }
+}
+
+///Target under the cursor, the last drawn one if several overlap
+fn target_at(column: u16, row: u16) -> Option<Target> {
+    let targets = TARGETS.lock().ok()?;
+    targets
+        .iter()
+        .rev()
+        .find(|(area, _)| area.contains(Position::new(column, row)))
+        .map(|(_, target)| *target)
+}
+
+///Turn clicks on keys and scrolling over lists into the matching key presses
+pub(crate) fn translate(event: Event) -> Event {
+    let Event::Mouse(mouse) = event else {
+        return event;
+    };
+    let code = match (mouse.kind, target_at(mouse.column, mouse.row)) {
+        (MouseEventKind::Down(MouseButton::Left), Some(Target::Key(code))) => code,
+        (MouseEventKind::ScrollUp, Some(Target::Row(_))) => KeyCode::Up,
+        (MouseEventKind::ScrollDown, Some(Target::Row(_))) => KeyCode::Down,
+        _ => return event,
+    };
+    Event::Key(KeyEvent::new(code, KeyModifiers::NONE))
+}
+
+///Row of a list clicked, None for any other event
+pub(crate) fn clicked_row(event: &Event) -> Option<usize> {
+    let Event::Mouse(mouse) = event else {
+        return None;
+    };
+    match (mouse.kind, target_at(mouse.column, mouse.row)) {
+        (MouseEventKind::Down(MouseButton::Left), Some(Target::Row(row))) => Some(row),
+        _ => None,
+    }
+}
@@ -0,0 +1,175 @@
+use anyhow::{Result, anyhow};
+
+///Why a game ended
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+pub(crate) enum EndReason {
+    ///A player reached the winning score
+    #[default]
+    Normal,
+    ///The loser left the game
+    Forfeit,
+    ///The loser lost its connection to the server
+    Disconnect,
+}
+
+impl EndReason {
+    fn from_value(value: &serde_json::Value) -> EndReason {
+        match value.as_str() {
+            Some("forfeit") | Some("surrender") | Some("left") => EndReason::Forfeit,
+            Some("disconnect") | Some("disconnected") | Some("timeout") => EndReason::Disconnect,
+            _ => EndReason::Normal,
+        }
+    }
+}
+
+///How a game ended, as told on the end-game screen
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+pub(crate) enum GameEnd {
+    ///The server ended the game normally
+    #[default]
+    Finished,
+    ///The opponent forfeited or lost its connection
+    OpponentLeft,
+    ///The player left the game
+    Forfeited,
+    ///The server ended the game because the player's connection dropped
+    Disconnected,
+    ///The connection to the game server was lost
+    ConnectionLost,
+}
+
+impl GameEnd {
+    ///End of the game seen by the given player
+    pub(crate) fn from_outcome(outcome: &GameOutcome, id: u64) -> GameEnd {
+        match (outcome.reason, outcome.won_by(id)) {
+            (EndReason::Normal, _) => GameEnd::Finished,
+            (_, true) => GameEnd::OpponentLeft,
+            (EndReason::Forfeit, false) => GameEnd::Forfeited,
+            (EndReason::Disconnect, false) => GameEnd::Disconnected,
+        }
+    }
+}
+
+///End-of-game message sent by the server
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+pub(crate) struct GameOutcome {
+    pub(crate) winner: u64,
+    ///Final score, left player first, when sent by the server
+    pub(crate) scores: Option<(u8, u8)>,
+    pub(crate) reason: EndReason,
+}
+
+impl GameOutcome {
+    ///Parse the text frame ending a game
+    ///
+    /// The server sends either an object holding the winner's id, or the id alone
+    ///
+    /// #Errors
+    /// Returns an error if no winner id is found in the message
+    pub(crate) fn parse(text: &str) -> Result<GameOutcome> {
+        let value: serde_json::Value = match serde_json::from_str(text) {
+            Ok(value) => value,
+            _ => serde_json::Value::String(text.trim().to_string()),
+        };
+        if let Some(winner) = as_id(&value) {
+            return Ok(GameOutcome {
+                winner,
+                ..Default::default()
+            });
+        }
+        let winner = ["winnerId", "winner", "winner_id"]
+            .iter()
+            .find_map(|key| as_id(&value[key]));
+        let Some(winner) = winner else {
+            return Err(anyhow!("No winner in end of game message"));
+        };
+        let score = |key: &str| value[key].as_u64().map(|score| score as u8);
+        let scores = match (score("player1Score"), score("player2Score")) {
+            (Some(score1), Some(score2)) => Some((score1, score2)),
+            _ => value["scores"].as_array().and_then(|scores| {
+                Some((scores.first()?.as_u64()? as u8, scores.get(1)?.as_u64()? as u8))
+            }),
+        };
+        Ok(GameOutcome {
+            winner,
+            scores,
+            reason: EndReason::from_value(&value["reason"]),
+        })
+    }
+    pub(crate) fn won_by(&self, id: u64) -> bool {
+        self.winner == id
+    }
+}
+
+///Id given as a number or as a string of digits
+fn as_id(value: &serde_json::Value) -> Option<u64> {
+    match value {
+        serde_json::Value::Number(number) => number.as_u64(),
+        serde_json::Value::String(text) => text.trim().parse().ok(),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ids_are_not_matched_as_substrings() {
+        let outcome = GameOutcome::parse(r#"{"winnerId": 12, "endedAt": 1712345678}"#).unwrap();
+        assert!(outcome.won_by(12));
+        assert!(!outcome.won_by(1));
+        assert!(!outcome.won_by(2));
+        assert!(!outcome.won_by(123));
+    }
+
+    #[test]
+    fn winner_as_string() {
+        let outcome = GameOutcome::parse(r#"{"winner": "21"}"#).unwrap();
+        assert_eq!(outcome.winner, 21);
+        assert!(!outcome.won_by(1));
+    }
+
+    #[test]
+    fn bare_id() {
+        assert_eq!(GameOutcome::parse("7").unwrap().winner, 7);
+        assert_eq!(GameOutcome::parse(r#""42""#).unwrap().winner, 42);
+        assert!(!GameOutcome::parse("42").unwrap().won_by(4));
+    }
+
+    #[test]
+    fn scores_and_reason() {
+        let outcome = GameOutcome::parse(
+            r#"{"winnerId": 3, "player1Score": 5, "player2Score": 2, "reason": "normal"}"#,
+        )
+        .unwrap();
+        assert_eq!(outcome.scores, Some((5, 2)));
+        assert_eq!(outcome.reason, EndReason::Normal);
+        let outcome =
+            GameOutcome::parse(r#"{"winnerId": 3, "scores": [1, 0], "reason": "forfeit"}"#)
+                .unwrap();
+        assert_eq!(outcome.scores, Some((1, 0)));
+        assert_eq!(outcome.reason, EndReason::Forfeit);
+        let outcome = GameOutcome::parse(r#"{"winnerId": 3, "reason": "disconnect"}"#).unwrap();
+        assert_eq!(outcome.scores, None);
+        assert_eq!(outcome.reason, EndReason::Disconnect);
+    }
+
+    #[test]
+    fn end_seen_by_each_player() {
+        let outcome = GameOutcome::parse(r#"{"winnerId": 1, "reason": "forfeit"}"#).unwrap();
+        assert_eq!(GameEnd::from_outcome(&outcome, 1), GameEnd::OpponentLeft);
+        assert_eq!(GameEnd::from_outcome(&outcome, 11), GameEnd::Forfeited);
+        let outcome = GameOutcome::parse(r#"{"winnerId": 1, "reason": "timeout"}"#).unwrap();
+        assert_eq!(GameEnd::from_outcome(&outcome, 10), GameEnd::Disconnected);
+        let outcome = GameOutcome::parse("1").unwrap();
+        assert_eq!(GameEnd::from_outcome(&outcome, 10), GameEnd::Finished);
+    }
+
+    #[test]
+    fn missing_winner() {
+        assert!(GameOutcome::parse(r#"{"reason": "forfeit", "loserId": 1}"#).is_err());
+        assert!(GameOutcome::parse("game over").is_err());
+        assert!(GameOutcome::parse("-1").is_err());
+    }
+}
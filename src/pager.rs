@@ -0,0 +1,65 @@
+use std::ops::Range;
+
+///Cursor over a list displayed one terminal-sized page at a time
+#[derive(Default)]
+pub(crate) struct Pager {
+    ///Displayed page
+    pub(crate) index: usize,
+    pub(crate) index_max: usize,
+    ///Position of the cursor in the whole list
+    pub(crate) selected: usize,
+    page_height: usize,
+    len: usize,
+}
+
+impl Pager {
+    ///Update pages depending on the list's length and the lines available to display it
+    pub(crate) fn update(&mut self, len: usize, page_height: usize) {
+        self.len = len;
+        self.page_height = page_height;
+        if self.selected >= len {
+            self.selected = len.saturating_sub(1);
+        }
+        self.update_page();
+    }
+    ///Update pages after the list changed, keeping the same page height
+    pub(crate) fn set_len(&mut self, len: usize) {
+        self.update(len, self.page_height);
+    }
+    ///Keep the displayed page on the cursor
+    fn update_page(&mut self) {
+        if self.page_height < self.len && self.page_height != 0 {
+            self.index_max = (self.len - 1) / self.page_height;
+            self.index = self.selected / self.page_height;
+        } else {
+            self.index_max = 0;
+            self.index = 0;
+        }
+    }
+    ///Move the cursor, clamped to the list
+    pub(crate) fn select(&mut self, selected: usize) {
+        self.selected = selected.min(self.len.saturating_sub(1));
+        self.update_page();
+    }
+    pub(crate) fn select_previous(&mut self) {
+        self.select(self.selected.saturating_sub(1));
+    }
+    pub(crate) fn select_next(&mut self) {
+        self.select(self.selected + 1);
+    }
+    pub(crate) fn previous_page(&mut self) {
+        self.select(self.selected.saturating_sub(self.page_height.max(1)));
+    }
+    pub(crate) fn next_page(&mut self) {
+        self.select(self.selected + self.page_height.max(1));
+    }
+    ///Indexes of the list drawn on the current page
+    ///
+    /// #Parameters
+    /// - height: lines available to draw the list
+    pub(crate) fn visible(&self, height: usize) -> Range<usize> {
+        let height = height.max(1);
+        let min = (self.index * height).min(self.len);
+        min..(min + height).min(self.len)
+    }
+}
@@ -0,0 +1,60 @@
+use std::time::{Duration, Instant};
+
+///Longest pause granted when the server does not tell its timeout
+const PAUSE_TIMEOUT: Duration = Duration::from_secs(30);
+///Longest wait for the server while paused, before updating the countdown
+pub(crate) const PAUSE_REDRAW: Duration = Duration::from_secs(1);
+///Message asking the game server to pause the game
+pub(crate) const PAUSE_MESSAGE: &str = r#"{"type":"pause"}"#;
+///Message asking the game server to resume the game
+pub(crate) const RESUME_MESSAGE: &str = r#"{"type":"resume"}"#;
+
+///Pause notification sent by the game server
+pub(crate) enum PauseNotice {
+    Paused { by: u64, timeout: Duration },
+    Resumed,
+}
+
+impl PauseNotice {
+    ///Parse a text frame of the game server, None if it is not about pauses
+    pub(crate) fn parse(text: &str) -> Option<PauseNotice> {
+        let value: serde_json::Value = serde_json::from_str(text).ok()?;
+        match value["type"].as_str()? {
+            "pause" | "paused" => Some(PauseNotice::Paused {
+                by: value["by"].as_u64().or(value["userId"].as_u64())?,
+                timeout: value["timeout"]
+                    .as_u64()
+                    .map(Duration::from_secs)
+                    .unwrap_or(PAUSE_TIMEOUT),
+            }),
+            "resume" | "resumed" => Some(PauseNotice::Resumed),
+            _ => None,
+        }
+    }
+}
+
+///A pause of an online game
+pub(crate) struct Pause {
+    ///Id of the player who paused the game
+    pub(crate) by: u64,
+    since: Instant,
+    timeout: Duration,
+}
+
+impl Pause {
+    pub(crate) fn new(by: u64, timeout: Duration) -> Pause {
+        Pause {
+            by,
+            since: Instant::now(),
+            timeout,
+        }
+    }
+    ///Time before the server resumes the game by itself
+    pub(crate) fn remaining(&self) -> Duration {
+        self.timeout.saturating_sub(self.since.elapsed())
+    }
+    ///Whether the server should have resumed the game for longer than the grace period
+    pub(crate) fn overdue(&self, grace: Duration) -> bool {
+        self.since.elapsed() > self.timeout + grace
+    }
+}
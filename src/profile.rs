@@ -0,0 +1,213 @@
+use crate::Context;
+use crate::directory::get_users;
+use crate::friends::Presence;
+use crate::pager::Pager;
+use anyhow::{Result, anyhow};
+use std::rc::Rc;
+
+#[derive(Clone, Default)]
+pub(crate) struct Profile {
+    pub(crate) id: u64,
+    pub(crate) name: String,
+    pub(crate) presence: Presence,
+    pub(crate) guest: bool,
+    pub(crate) wins: u64,
+    pub(crate) losses: u64,
+}
+
+///A finished game, seen from the profile's owner side
+#[derive(Clone, Default)]
+pub(crate) struct MatchRecord {
+    pub(crate) opponent: String,
+    pub(crate) score: u64,
+    pub(crate) opponent_score: u64,
+    pub(crate) won: bool,
+    pub(crate) date: String,
+    ///Length of the game in seconds, when known
+    pub(crate) duration: Option<u64>,
+}
+
+impl MatchRecord {
+    ///Build a record from a game object sent by the server
+    ///
+    /// #Parameters
+    /// - value: json game object
+    /// - id: id of the profile's owner
+    ///
+    /// #Return
+    /// The record and the opponent's id, the opponent's name being filled later
+    fn from_value(value: &serde_json::Value, id: u64) -> Option<(MatchRecord, u64)> {
+        let player1 = value["player1_id"].as_u64()?;
+        let player2 = value["player2_id"].as_u64()?;
+        let score1 = value["player1_score"].as_u64().unwrap_or_default();
+        let score2 = value["player2_score"].as_u64().unwrap_or_default();
+        let (opponent_id, score, opponent_score) = match player1 == id {
+            true => (player2, score1, score2),
+            false => (player1, score2, score1),
+        };
+        let won = match value["winner_id"].as_u64() {
+            Some(winner) => winner == id,
+            _ => score > opponent_score,
+        };
+        let date = value["created_at"]
+            .as_str()
+            .or(value["date"].as_str())
+            .unwrap_or_default()
+            .to_string();
+        let record = MatchRecord {
+            score,
+            opponent_score,
+            won,
+            date,
+            duration: value["duration"].as_u64(),
+            ..Default::default()
+        };
+        Some((record, opponent_id))
+    }
+    pub(crate) fn printable_duration(&self) -> String {
+        match self.duration {
+            Some(seconds) => format!("{}:{:02}", seconds / 60, seconds % 60),
+            _ => "-".to_string(),
+        }
+    }
+}
+
+impl Profile {
+    ///Fetch the public profile of a user
+    ///
+    /// #Errors
+    /// Returns an error if the server does not know the user
+    pub(crate) async fn fetch(context: Rc<Context>, id: u64) -> Result<Profile> {
+        let apiloc = format!(
+            "https://{}/api/user/get_profile_id?user_id={}",
+            context.location, id
+        );
+        let response = context.client.get(apiloc).send().await?;
+        let response: serde_json::Value = response.json().await?;
+        match Profile::from_value(&response, id) {
+            Some(profile) => Ok(profile),
+            _ => Err(anyhow!("User not found")),
+        }
+    }
+    ///Build a profile from a user object sent by the server
+    ///
+    /// #Parameters
+    /// - value: json user object
+    /// - id: id to use if the object does not contain one
+    pub(crate) fn from_value(value: &serde_json::Value, id: u64) -> Option<Profile> {
+        let wins = value["wins"].as_u64().unwrap_or_default();
+        let losses = match value["losses"].as_u64() {
+            Some(losses) => losses,
+            _ => value["games_played"]
+                .as_u64()
+                .unwrap_or_default()
+                .saturating_sub(wins),
+        };
+        Some(Profile {
+            id: value["id"].as_u64().unwrap_or(id),
+            name: value["name"].as_str()?.to_string(),
+            presence: Presence::from_value(&value["status"]).unwrap_or_default(),
+            guest: value["is_guest"]
+                .as_bool()
+                .or(value["is_guest"].as_u64().map(|guest| guest != 0))
+                .unwrap_or_default(),
+            wins,
+            losses,
+        })
+    }
+    ///Percentage of games won
+    pub(crate) fn win_rate(&self) -> f64 {
+        match self.wins + self.losses {
+            0 => 0.0,
+            played => self.wins as f64 * 100.0 / played as f64,
+        }
+    }
+}
+
+///Fetch the games played by a user, most recent first
+///
+/// #Errors
+/// Returns an error if the request fails or its response is not valid JSON,
+/// opponents whose name can not be fetched being shown by id
+pub(crate) async fn fetch_history(context: Rc<Context>, id: u64) -> Result<Vec<MatchRecord>> {
+    let apiloc = format!(
+        "https://{}/api/user/get_history?user_id={}",
+        context.location, id
+    );
+    let response = context.client.get(apiloc).send().await?;
+    if !response.status().is_success() {
+        return Ok(vec![]);
+    }
+    let response: serde_json::Value = response.json().await?;
+    let records: Vec<(MatchRecord, u64)> = match response.as_array() {
+        Some(games) => games
+            .iter()
+            .filter_map(|game| MatchRecord::from_value(game, id))
+            .collect(),
+        _ => return Ok(vec![]),
+    };
+    let opponent_ids: Vec<u64> = records.iter().map(|(_, opponent)| *opponent).collect();
+    let opponents = get_users(context, &opponent_ids).await;
+    let mut history: Vec<MatchRecord> = records
+        .into_iter()
+        .map(|(mut record, opponent)| {
+            record.opponent = match opponents.get(&opponent) {
+                Some(profile) => profile.name.clone(),
+                _ => format!("#{opponent}"),
+            };
+            record
+        })
+        .collect();
+    history.sort_by(|a, b| b.date.cmp(&a.date));
+    Ok(history)
+}
+
+///Number of consecutive games with the same result as the last one
+///
+/// #Return
+/// The length of the streak and whether it is a winning one
+pub(crate) fn current_streak(history: &[MatchRecord]) -> (usize, bool) {
+    match history.first() {
+        Some(last) => (
+            history
+                .iter()
+                .take_while(|record| record.won == last.won)
+                .count(),
+            last.won,
+        ),
+        _ => (0, false),
+    }
+}
+
+///Height of the profile summary drawn above the match history
+pub(crate) const PROFILE_HEADER_HEIGHT: u16 = 9;
+
+///State of the Profile screen
+#[derive(Default)]
+pub(crate) struct ProfileView {
+    pub(crate) profile: Profile,
+    pub(crate) history: Vec<MatchRecord>,
+    pub(crate) pager: Pager,
+}
+
+impl ProfileView {
+    ///Fetch a user's profile and match history
+    pub(crate) async fn load(context: Rc<Context>, id: u64) -> Result<ProfileView> {
+        let profile = Profile::fetch(context.clone(), id).await?;
+        let history = fetch_history(context, id).await?;
+        Ok(ProfileView {
+            profile,
+            history,
+            ..Default::default()
+        })
+    }
+    ///Update match history pages depending on the terminal's size
+    pub(crate) fn update_index(&mut self, terminal: &mut ratatui::DefaultTerminal) {
+        let page_height = terminal
+            .get_frame()
+            .area()
+            .height
+            .saturating_sub(PROFILE_HEADER_HEIGHT + 3) as usize;
+        self.pager.update(self.history.len(), page_height);
+    }
+}
@@ -0,0 +1,124 @@
+use crate::login::Auth;
+use anyhow::{Result, anyhow};
+use std::cell::RefCell;
+use std::rc::Rc;
+
+///Rematch messages exchanged through the chat websocket
+#[derive(Clone, Copy, PartialEq)]
+pub(crate) enum RematchAction {
+    Propose,
+    Accept,
+    Decline,
+    ///The proposal was withdrawn, its sender having left the end-game screen
+    Cancel,
+}
+
+impl RematchAction {
+    pub(crate) fn from_value(value: &serde_json::Value) -> Option<RematchAction> {
+        match value.as_str()? {
+            "propose" => Some(RematchAction::Propose),
+            "accept" => Some(RematchAction::Accept),
+            "decline" => Some(RematchAction::Decline),
+            "cancel" => Some(RematchAction::Cancel),
+            _ => None,
+        }
+    }
+    fn as_str(&self) -> &'static str {
+        match self {
+            RematchAction::Propose => "propose",
+            RematchAction::Accept => "accept",
+            RematchAction::Decline => "decline",
+            RematchAction::Cancel => "cancel",
+        }
+    }
+}
+
+///Progress of a rematch on the end-game screen
+#[derive(Clone, Copy, PartialEq, Default, Debug)]
+pub(crate) enum RematchState {
+    #[default]
+    None,
+    ///Proposed to the opponent, waiting for an answer
+    Proposed,
+    ///Proposed by the opponent
+    Received,
+    Accepted,
+    ///Both players proposed at the same time
+    Crossed,
+    Declined,
+    ///The opponent left the end-game screen
+    Left,
+}
+
+impl RematchState {
+    ///Update the state with a message of the opponent
+    pub(crate) fn receive(&mut self, action: RematchAction) {
+        *self = match (*self, action) {
+            (RematchState::Proposed, RematchAction::Propose) => RematchState::Crossed,
+            (_, RematchAction::Propose) => RematchState::Received,
+            (RematchState::Proposed, RematchAction::Accept) => RematchState::Accepted,
+            (RematchState::Proposed, RematchAction::Decline) => RematchState::Declined,
+            (_, RematchAction::Cancel) => RematchState::Left,
+            (state, _) => state,
+        };
+    }
+}
+
+///Send a rematch message to the opponent through the chat websocket
+///
+/// #Errors
+/// Returns an error if the chat websocket is closed
+pub(crate) fn send_rematch(
+    auth: &Rc<RefCell<Auth>>,
+    action: RematchAction,
+    opponent: u64,
+) -> Result<()> {
+    let auth = auth.borrow();
+    let message = serde_json::json!({
+        "type": "rematch",
+        "action": action.as_str(),
+        "from": auth.id,
+        "to": opponent,
+    });
+    match &auth.chat {
+        Some(chat) => chat
+            .try_send(message)
+            .map_err(|_| anyhow!("Chat connection lost")),
+        _ => Err(anyhow!("Not connected to the chat")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn received(state: RematchState, action: RematchAction) -> RematchState {
+        let mut state = state;
+        state.receive(action);
+        state
+    }
+
+    #[test]
+    fn answers_to_a_proposal() {
+        let proposed = RematchState::Proposed;
+        assert_eq!(received(proposed, RematchAction::Accept), RematchState::Accepted);
+        assert_eq!(received(proposed, RematchAction::Decline), RematchState::Declined);
+        assert_eq!(received(proposed, RematchAction::Cancel), RematchState::Left);
+        assert_eq!(received(proposed, RematchAction::Propose), RematchState::Crossed);
+    }
+
+    #[test]
+    fn proposal_of_the_opponent() {
+        assert_eq!(received(RematchState::None, RematchAction::Propose), RematchState::Received);
+        let state = received(RematchState::Received, RematchAction::Cancel);
+        assert_eq!(state, RematchState::Left);
+    }
+
+    #[test]
+    fn answers_without_a_proposal_are_ignored() {
+        assert_eq!(received(RematchState::None, RematchAction::Accept), RematchState::None);
+        assert_eq!(received(RematchState::None, RematchAction::Decline), RematchState::None);
+        let state = received(RematchState::Received, RematchAction::Accept);
+        assert_eq!(state, RematchState::Received);
+    }
+}
@@ -0,0 +1,254 @@
+use crate::game::GameStats;
+use crate::history::data_dir;
+use anyhow::{Result, anyhow};
+use std::fs;
+use std::io::Write;
+use std::path::PathBuf;
+use std::time::Instant;
+
+///Playback speeds available in the Replay screen
+pub(crate) const SPEEDS: [f64; 5] = [0.25, 0.5, 1.0, 2.0, 4.0];
+///Default playback speed index, 1x
+const DEFAULT_SPEED: usize = 2;
+///Jump applied by a seek, in milliseconds
+const SEEK_STEP: u64 = 5000;
+
+///Game state decoded at a given time of the match
+#[derive(Clone, Copy)]
+pub(crate) struct ReplayFrame {
+    ///Milliseconds elapsed since the beginning of the match
+    pub(crate) at: u64,
+    pub(crate) stats: GameStats,
+}
+
+impl ReplayFrame {
+    fn to_value(self) -> serde_json::Value {
+        serde_json::json!([
+            self.at,
+            self.stats.left_y,
+            self.stats.right_y,
+            self.stats.ball_x,
+            self.stats.ball_y,
+            self.stats.player1_score,
+            self.stats.player2_score,
+        ])
+    }
+    fn from_value(value: &serde_json::Value) -> Option<ReplayFrame> {
+        let float = |index: usize| value[index].as_f64().map(|float| float as f32);
+        Some(ReplayFrame {
+            at: value[0].as_u64()?,
+            stats: GameStats {
+                left_y: float(1)?,
+                right_y: float(2)?,
+                ball_x: float(3)?,
+                ball_y: float(4)?,
+                player1_score: value[5].as_u64()? as u8,
+                player2_score: value[6].as_u64()? as u8,
+                winner: false,
+            },
+        })
+    }
+}
+
+///Frames received during a match
+#[derive(Default)]
+pub(crate) struct Recorder {
+    start: Option<Instant>,
+    pub(crate) frames: Vec<ReplayFrame>,
+}
+
+impl Recorder {
+    pub(crate) fn push(&mut self, stats: &GameStats) {
+        let start = *self.start.get_or_insert_with(Instant::now);
+        self.frames.push(ReplayFrame {
+            at: start.elapsed().as_millis() as u64,
+            stats: *stats,
+        });
+    }
+}
+
+///File of the replay of a match, named after its start and the opponent
+/// so that matches started in the same second are kept apart
+fn replay_path(started_at: u64, opponent: &str) -> Result<PathBuf> {
+    let opponent: String = opponent
+        .chars()
+        .map(|c| match c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+            true => c,
+            false => '_',
+        })
+        .collect();
+    Ok(data_dir()?
+        .join("replays")
+        .join(format!("{started_at}-{opponent}.jsonl")))
+}
+
+///File of a replay saved before the opponent was part of the name
+fn legacy_replay_path(started_at: u64) -> Result<PathBuf> {
+    Ok(data_dir()?
+        .join("replays")
+        .join(format!("{started_at}.jsonl")))
+}
+
+///Write the frames of a match to its replay file
+///
+/// The first line holds the opponent's name, then one frame per line
+///
+/// #Parameters
+/// - started_at: Unix timestamp of the match, identifying it in the local history
+/// - opponent: name of the opponent, telling apart matches started in the same second
+pub(crate) fn save_replay(started_at: u64, opponent: &str, frames: &[ReplayFrame]) -> Result<()> {
+    if frames.is_empty() {
+        return Ok(());
+    }
+    let path = replay_path(started_at, opponent)?;
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    let mut file = fs::File::create(path)?;
+    writeln!(file, "{}", serde_json::json!({ "opponent": opponent }))?;
+    for frame in frames {
+        writeln!(file, "{}", frame.to_value())?;
+    }
+    Ok(())
+}
+
+///Playback state of the Replay screen
+pub(crate) struct ReplayPlayer {
+    pub(crate) opponent: String,
+    pub(crate) frames: Vec<ReplayFrame>,
+    ///Index of the displayed frame
+    pub(crate) current: usize,
+    ///Playback position, in milliseconds since the beginning of the match
+    pub(crate) position: f64,
+    pub(crate) speed: usize,
+    pub(crate) paused: bool,
+    last_tick: Instant,
+}
+
+impl Default for ReplayPlayer {
+    fn default() -> Self {
+        ReplayPlayer {
+            opponent: String::new(),
+            frames: vec![],
+            current: 0,
+            position: 0.0,
+            speed: DEFAULT_SPEED,
+            paused: false,
+            last_tick: Instant::now(),
+        }
+    }
+}
+
+impl ReplayPlayer {
+    ///Load the replay of a match of the local history
+    ///
+    /// #Errors
+    /// Returns an error if the match was not recorded
+    pub(crate) fn load(started_at: u64, opponent: &str) -> Result<ReplayPlayer> {
+        let content = match fs::read_to_string(replay_path(started_at, opponent)?) {
+            Ok(content) => Ok(content),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                fs::read_to_string(legacy_replay_path(started_at)?)
+            }
+            Err(e) => Err(e),
+        };
+        let content = match content {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                return Err(anyhow!("No replay recorded for this match"));
+            }
+            Err(e) => return Err(e.into()),
+        };
+        let mut lines = content
+            .lines()
+            .filter_map(|line| serde_json::from_str::<serde_json::Value>(line).ok());
+        let opponent = match lines.next() {
+            Some(header) => header["opponent"].as_str().unwrap_or_default().to_string(),
+            _ => return Err(anyhow!("Empty replay")),
+        };
+        let frames: Vec<ReplayFrame> = lines
+            .filter_map(|value| ReplayFrame::from_value(&value))
+            .collect();
+        if frames.is_empty() {
+            return Err(anyhow!("Empty replay"));
+        }
+        Ok(ReplayPlayer {
+            opponent,
+            frames,
+            ..Default::default()
+        })
+    }
+    pub(crate) fn stats(&self) -> GameStats {
+        self.frames
+            .get(self.current)
+            .map(|frame| frame.stats)
+            .unwrap_or_default()
+    }
+    pub(crate) fn duration(&self) -> u64 {
+        self.frames.last().map(|frame| frame.at).unwrap_or_default()
+    }
+    pub(crate) fn speed_factor(&self) -> f64 {
+        SPEEDS[self.speed]
+    }
+    ///Advance the playback by the time elapsed since the last call
+    pub(crate) fn update(&mut self) {
+        let elapsed = self.last_tick.elapsed().as_secs_f64() * 1000.0;
+        self.last_tick = Instant::now();
+        if self.paused {
+            return;
+        }
+        self.position = (self.position + elapsed * self.speed_factor()).min(self.duration() as f64);
+        self.sync_frame();
+        if self.position >= self.duration() as f64 {
+            self.paused = true;
+        }
+    }
+    ///Display the last frame received before the playback position
+    fn sync_frame(&mut self) {
+        let position = self.position as u64;
+        self.current = self
+            .frames
+            .partition_point(|frame| frame.at <= position)
+            .saturating_sub(1);
+    }
+    pub(crate) fn toggle_pause(&mut self) {
+        if self.paused && self.position >= self.duration() as f64 {
+            self.position = 0.0;
+            self.sync_frame();
+        }
+        self.paused = !self.paused;
+    }
+    pub(crate) fn faster(&mut self) {
+        self.speed = (self.speed + 1).min(SPEEDS.len() - 1);
+    }
+    pub(crate) fn slower(&mut self) {
+        self.speed = self.speed.saturating_sub(1);
+    }
+    pub(crate) fn seek_forward(&mut self) {
+        self.position = (self.position + SEEK_STEP as f64).min(self.duration() as f64);
+        self.sync_frame();
+    }
+    pub(crate) fn seek_backward(&mut self) {
+        self.position = (self.position - SEEK_STEP as f64).max(0.0);
+        self.sync_frame();
+    }
+    ///Display the given frame, moving the playback position on it
+    pub(crate) fn show_frame(&mut self, index: usize) {
+        self.current = index.min(self.frames.len().saturating_sub(1));
+        self.position = self
+            .frames
+            .get(self.current)
+            .map(|frame| frame.at)
+            .unwrap_or_default() as f64;
+    }
+    ///Pause and display the next frame
+    pub(crate) fn step_forward(&mut self) {
+        self.paused = true;
+        self.show_frame(self.current + 1);
+    }
+    ///Pause and display the previous frame
+    pub(crate) fn step_backward(&mut self) {
+        self.paused = true;
+        self.show_frame(self.current.saturating_sub(1));
+    }
+}
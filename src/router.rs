@@ -0,0 +1,148 @@
+use crate::CurrentScreen;
+use std::cell::RefCell;
+
+///Screen whose Back leads to the given one when it was not opened from there:
+/// the menu it is listed in. None for the main menus
+pub(crate) fn parent(screen: CurrentScreen) -> Option<CurrentScreen> {
+    let parent = match screen {
+        CurrentScreen::FirstScreen | CurrentScreen::Welcome => return None,
+        CurrentScreen::SignUp | CurrentScreen::Login | CurrentScreen::LocalSetup => {
+            CurrentScreen::FirstScreen
+        }
+        CurrentScreen::LocalStandings => CurrentScreen::LocalSetup,
+        CurrentScreen::LocalPlay => CurrentScreen::LocalStandings,
+        CurrentScreen::GameChoice | CurrentScreen::SocialLife | CurrentScreen::Profile => {
+            CurrentScreen::Welcome
+        }
+        CurrentScreen::CreateGame
+        | CurrentScreen::StartGame
+        | CurrentScreen::PlayGame
+        | CurrentScreen::EndGame
+        | CurrentScreen::LiveGames
+        | CurrentScreen::LocalHistory
+        | CurrentScreen::Tournaments => CurrentScreen::GameChoice,
+        CurrentScreen::Replay => CurrentScreen::LocalHistory,
+        CurrentScreen::Bracket => CurrentScreen::Tournaments,
+        CurrentScreen::FriendsDisplay | CurrentScreen::Leaderboard => CurrentScreen::SocialLife,
+        CurrentScreen::AddFriend | CurrentScreen::FriendRequests => CurrentScreen::FriendsDisplay,
+        CurrentScreen::ErrorScreen => CurrentScreen::FirstScreen,
+    };
+    Some(parent)
+}
+
+///Navigation stack: the displayed screen and the screens it was opened from
+pub(crate) struct Router {
+    ///Screens opened on the way to the displayed one, which is last
+    stack: RefCell<Vec<CurrentScreen>>,
+}
+
+impl Default for Router {
+    fn default() -> Self {
+        Router::new(CurrentScreen::default())
+    }
+}
+
+impl Router {
+    pub(crate) fn new(root: CurrentScreen) -> Router {
+        Router {
+            stack: RefCell::new(vec![root]),
+        }
+    }
+    ///Displayed screen
+    pub(crate) fn get(&self) -> CurrentScreen {
+        self.stack.borrow().last().copied().unwrap_or_default()
+    }
+    ///Whether the screen is displayed or in the history
+    pub(crate) fn contains(&self, screen: CurrentScreen) -> bool {
+        self.stack.borrow().contains(&screen)
+    }
+    ///Open a screen over the displayed one, Back returning to it
+    pub(crate) fn push(&self, screen: CurrentScreen) {
+        let mut stack = self.stack.borrow_mut();
+        if stack.last() != Some(&screen) {
+            stack.push(screen);
+        }
+    }
+    ///Display another screen in place of the displayed one, Back skipping it
+    pub(crate) fn replace(&self, screen: CurrentScreen) {
+        let mut stack = self.stack.borrow_mut();
+        stack.pop();
+        stack.push(screen);
+    }
+    ///Return to the screen the displayed one was opened from,
+    /// or to its parent when it was opened directly
+    ///
+    /// #Return
+    /// false on the main menus, which have nothing to go back to
+    pub(crate) fn back(&self) -> bool {
+        let screen = self.get();
+        let mut stack = self.stack.borrow_mut();
+        if stack.len() > 1 {
+            stack.pop();
+            return true;
+        }
+        match parent(screen) {
+            Some(parent) => {
+                drop(stack);
+                self.deep_link(parent);
+                true
+            }
+            _ => false,
+        }
+    }
+    ///Open a screen from anywhere, as if it had been reached through the menus:
+    /// Back then goes through its parents up to the main menu
+    pub(crate) fn deep_link(&self, screen: CurrentScreen) {
+        let mut path = vec![screen];
+        while let Some(parent) = parent(path[path.len() - 1]) {
+            path.push(parent);
+        }
+        path.reverse();
+        *self.stack.borrow_mut() = path;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn back_after_deep_link() {
+        let router = Router::new(CurrentScreen::Welcome);
+        router.deep_link(CurrentScreen::AddFriend);
+        assert_eq!(router.get(), CurrentScreen::AddFriend);
+        for screen in [
+            CurrentScreen::FriendsDisplay,
+            CurrentScreen::SocialLife,
+            CurrentScreen::Welcome,
+        ] {
+            assert!(router.back());
+            assert_eq!(router.get(), screen);
+        }
+        assert!(!router.back());
+        assert_eq!(router.get(), CurrentScreen::Welcome);
+    }
+
+    #[test]
+    fn duplicate_push() {
+        let router = Router::new(CurrentScreen::Welcome);
+        router.push(CurrentScreen::Profile);
+        router.push(CurrentScreen::Profile);
+        assert!(router.back());
+        assert_eq!(router.get(), CurrentScreen::Welcome);
+    }
+
+    #[test]
+    fn replace_at_root() {
+        let router = Router::new(CurrentScreen::FirstScreen);
+        router.replace(CurrentScreen::Welcome);
+        assert_eq!(router.get(), CurrentScreen::Welcome);
+        assert!(!router.contains(CurrentScreen::FirstScreen));
+        assert!(!router.back());
+        router.replace(CurrentScreen::Bracket);
+        assert!(router.back());
+        assert_eq!(router.get(), CurrentScreen::Tournaments);
+        assert!(router.back());
+        assert_eq!(router.get(), CurrentScreen::GameChoice);
+    }
+}
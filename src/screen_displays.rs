@@ -1,90 +1,84 @@
-use ratatui::widgets::canvas::{Circle, Rectangle};
+use ratatui::widgets::canvas::{Circle, Context, Rectangle};
 use ratatui::{
     buffer::Buffer,
     layout::{Alignment, Rect},
-    prelude::{Color, Constraint, Direction, Layout},
+    prelude::{Constraint, Direction, Layout},
     style::{Modifier, Style, Stylize},
-    symbols::{Marker, border},
+    symbols::border,
     text::Line,
     text::Span,
-    widgets::{Block, Borders, Paragraph, Widget, canvas::Canvas},
+    widgets::{
+        Bar, BarChart, BarGroup, Block, Borders, Clear, List, ListItem, ListState, Paragraph, Row,
+        Sparkline, StatefulWidget, Table, TableState, Widget, Wrap, canvas::Canvas,
+    },
 };
+use crossterm::event::KeyCode;
+use std::sync::atomic::Ordering;
 
 use crate::Infos;
-use crate::LOGO;
+use crate::CurrentScreen;
+use crate::{LOGO, LOGO_HEIGHT};
+use crate::analytics::MatchAnalytics;
+use crate::court::{self, CourtConfig};
+use crate::friends::FriendStatus;
+use crate::game::GameStats;
+use crate::keymap;
+use crate::local_tournament::LocalTournament;
 use crate::login::Field;
+use crate::menu::{self, MenuAction};
+use crate::mouse::{self, Target};
+use crate::outcome::{EndReason, GameEnd};
+use crate::profile::{PROFILE_HEADER_HEIGHT, current_streak};
+use crate::rematch::RematchState;
+use crate::replay::ReplayPlayer;
+use crate::theme::theme;
+use crate::tournament::{
+    BracketMatch, MATCH_COUNTDOWN, TOURNAMENT_SIZES, Tournament, TournamentStatus,
+};
 
 pub(crate) trait ScreenDisplayer {
-    fn display_welcome_screen(&self, area: Rect, buf: &mut Buffer);
-    fn display_gamechoice_screen(&self, area: Rect, buf: &mut Buffer);
-    fn display_social_screen(&self, area: Rect, buf: &mut Buffer);
     fn display_friends_screen(&self, area: Rect, buf: &mut Buffer);
     fn display_waiting_screen(&self, area: Rect, buf: &mut Buffer);
-    fn display_first_screen(&self, area: Rect, buf: &mut Buffer);
+    fn display_menu_screen(&self, area: Rect, buf: &mut Buffer);
     fn display_played_game(&self, area: Rect, buf: &mut Buffer);
     fn display_endgame(&self, area: Rect, buf: &mut Buffer);
     fn display_signup_screen(&self, area: Rect, buf: &mut Buffer);
     fn display_login_screen(&self, area: Rect, buf: &mut Buffer);
     fn display_error_screen(&self, area: Rect, buf: &mut Buffer);
     fn display_addfriends_screen(&self, area: Rect, buf: &mut Buffer);
-    fn display_delete_friends_screen(&self, area: Rect, buf: &mut Buffer);
+    fn display_profile_screen(&self, area: Rect, buf: &mut Buffer);
+    fn display_friend_requests_screen(&self, area: Rect, buf: &mut Buffer);
+    fn display_leaderboard_screen(&self, area: Rect, buf: &mut Buffer);
+    fn display_history_screen(&self, area: Rect, buf: &mut Buffer);
+    fn display_replay_screen(&self, area: Rect, buf: &mut Buffer);
+    fn display_live_games_screen(&self, area: Rect, buf: &mut Buffer);
+    fn display_tournaments_screen(&self, area: Rect, buf: &mut Buffer);
+    fn display_bracket_screen(&self, area: Rect, buf: &mut Buffer);
+    fn display_local_setup_screen(&self, area: Rect, buf: &mut Buffer);
+    fn display_local_standings_screen(&self, area: Rect, buf: &mut Buffer);
+    fn display_local_play_screen(&self, area: Rect, buf: &mut Buffer);
     fn print_demo(&self, area: Rect, buf: &mut Buffer);
 }
 
 impl ScreenDisplayer for Infos {
-    fn display_first_screen(&self, area: Rect, buf: &mut Buffer) {
-        let layout = Layout::default()
-            .direction(Direction::Vertical)
-            .constraints(vec![Constraint::Max(10), Constraint::Fill(1)])
-            .split(area);
-        self.print_demo(layout[1], buf);
-        let instructions = Line::from(vec![
-            " Menu:".bold(),
-            "  ↑. Sign up".bold(),
-            "  ↓. Login".bold(),
-            "  →. Sign in as guest".bold(),
-            "  ESC. Quit ".bold(),
-        ]);
-        print_block(instructions, layout[0], buf);
-    }
-    fn display_welcome_screen(&self, area: Rect, buf: &mut Buffer) {
-        let layout = Layout::default()
-            .direction(Direction::Vertical)
-            .constraints(vec![Constraint::Max(10), Constraint::Fill(1)])
-            .split(area);
-        self.print_demo(layout[1], buf);
-        let instructions = Line::from(vec![
-            " Menu:  ↑ Game ".bold(),
-            " → Social Life ".bold(),
-            " ESC. Quit ".bold(),
-        ]);
-        print_block(instructions, layout[0], buf);
-    }
-    fn display_gamechoice_screen(&self, area: Rect, buf: &mut Buffer) {
-        let layout = Layout::default()
-            .direction(Direction::Vertical)
-            .constraints(vec![Constraint::Max(10), Constraint::Fill(1)])
-            .split(area);
-        self.print_demo(layout[1], buf);
-        let instructions = Line::from(vec![
-            " Menu: → Online ".bold(),
-            " ← Back  ".bold(),
-            "ESC. Quit ".bold(),
-        ]);
-        print_block(instructions, layout[0], buf);
-    }
-    fn display_social_screen(&self, area: Rect, buf: &mut Buffer) {
+    fn display_menu_screen(&self, area: Rect, buf: &mut Buffer) {
+        let screen = self.screen.get();
+        let entries = menu::entries(screen);
         let layout = Layout::default()
             .direction(Direction::Vertical)
-            .constraints(vec![Constraint::Max(10), Constraint::Fill(1)])
+            .constraints(vec![
+                Constraint::Max(LOGO_HEIGHT + entries.len() as u16 + 3),
+                Constraint::Fill(1),
+            ])
             .split(area);
         self.print_demo(layout[1], buf);
-        let instructions = Line::from(vec![
-            " Menu: → Your Friends  ".bold(),
-            "← Back  ".bold(),
-            "ESC. Quit ".bold(),
-        ]);
-        print_block(instructions, layout[0], buf);
+        let title = match screen {
+            CurrentScreen::Welcome => "Main menu",
+            CurrentScreen::GameChoice => "Game",
+            CurrentScreen::SocialLife => "Social life",
+            _ => "Menu",
+        };
+        print_menu(title, entries, self.menu.selected(screen), layout[0], buf);
     }
     fn display_waiting_screen(&self, area: Rect, buf: &mut Buffer) {
         let block = Block::bordered().title_bottom("Menu: ESC. Quit".bold().into_centered_line()).border_set(border::THICK);
@@ -95,101 +89,245 @@ impl ScreenDisplayer for Infos {
     }
     fn display_friends_screen(&self, area: Rect, buf: &mut Buffer) {
         let instructions = Line::from(vec![
-            " Menu: ↑ Add friend ".bold(),
-            " ↓ Delete friend ".bold(),
-            " ← Previous ".bold(),
-            " → Next ".bold(),
+            " Menu: ↑↓ Select ".bold(),
+            " ←→ Page ".bold(),
+            " Enter. Profile ".bold(),
+            " i. Invite ".bold(),
+            " x. Remove ".bold(),
+            " a. Add ".bold(),
+            " r. Requests ".bold(),
             " ESC. Back ".bold(),
         ]);
         let block = Block::bordered()
             .title(Line::from("Your Friends").bold().centered())
             .title_bottom(instructions.centered())
             .border_set(border::THICK);
-        let mut friends_display: Vec<String> = vec![];
-        let height: usize = (area.height - 2) as usize;
-        let max: usize =
-            match (self.friend.index * height + height + 1) >= self.friend.friends_list.len() {
-                true => self.friend.friends_list.len(),
-                false => (self.friend.index * height) + height + 1,
-            };
-        let min = match self.friend.index * height < self.friend.friends_list.len() {
-            true => self.friend.index * height,
-            false => self.friend.friends_list.len(),
-        };
-        for friend in &self.friend.friends_list[min..max] {
-            friends_display.push(friend.clone());
-        }
-        let lines: Vec<Line> = friends_display
+        let visible = self.friend.pager.visible(area.height.saturating_sub(2) as usize);
+        let min = visible.start;
+        let items: Vec<ListItem> = self.friend.friends_list[visible]
             .iter()
-            .map(|friend| Line::from(friend.clone().bold()))
+            .map(|friend| {
+                ListItem::new(Line::from(vec![
+                    Span::styled("● ", Style::default().fg(friend.presence.color())),
+                    friend.label().bold(),
+                    Span::styled(
+                        format!("  {}", friend.presence.label()),
+                        Style::default().fg(friend.presence.color()),
+                    ),
+                ]))
+            })
             .collect();
-        Paragraph::new(lines)
+        for row in 0..items.len() {
+            let y = area.y + 1 + row as u16;
+            let row_area = Rect::new(area.x + 1, y, area.width.saturating_sub(2), 1);
+            mouse::add_target(row_area, Target::Row(min + row));
+        }
+        let mut state =
+            ListState::default().with_selected(self.friend.pager.selected.checked_sub(min));
+        StatefulWidget::render(
+            List::new(items)
+                .block(block)
+                .highlight_style(Style::default().add_modifier(Modifier::REVERSED))
+                .highlight_symbol("> "),
+            area,
+            buf,
+            &mut state,
+        );
+        if self.friend.confirm_remove
+            && let Some(friend) = self.friend.selected_friend()
+        {
+            let popup = centered_rect(area, 50, 5);
+            Clear.render(popup, buf);
+            Paragraph::new(vec![
+                Line::from(format!("Remove {} from your friends?", friend.name).bold()),
+                Line::from(""),
+                Line::from("y. Yes   n. No".bold()),
+            ])
             .centered()
-            .block(block)
-            .render(area, buf);
+            .block(Block::bordered().border_set(border::THICK))
+            .render(popup, buf);
+        }
     }
     fn display_played_game(&self, area: Rect, buf: &mut Buffer) {
         let layout = Layout::default()
             .direction(Direction::Vertical)
             .constraints(vec![Constraint::Fill(1), Constraint::Max(3)])
             .split(area);
-        Canvas::default()
-            .block(Block::bordered().title("Pong".bold()))
-            .marker(Marker::Braille)
-            .x_bounds([0.0, 100.0])
-            .y_bounds([0.0, 100.0])
-            .paint(|ctx| {
-                ctx.draw(&Circle {
-                    x: self.game.game_stats.ball_x as f64,
-                    y: (100.0 - self.game.game_stats.ball_y) as f64,
-                    radius: 0.5,
-                    color: Color::Yellow,
-                });
-                ctx.draw(&Rectangle {
-                    x: 1.5,
-                    y: (95.0 - self.game.game_stats.left_y) as f64,
-                    width: 2.0,
-                    height: 10.0,
-                    color: Color::Green,
-                });
-                ctx.draw(&Rectangle {
-                    x: 97.0,
-                    y: (95.0 - self.game.game_stats.right_y) as f64,
-                    width: 2.0,
-                    height: 10.0,
-                    color: Color::Green,
-                });
+        let (title, player) = match self.game.spectator {
+            true => ("Spectating", self.game.player_name.as_str()),
+            false => ("Pong - p. Pause, ESC. Leave", "You"),
+        };
+        print_game(&self.game.game_stats, &self.game.config, title, layout[0], buf);
+        print_score(
+            &self.game.game_stats,
+            player,
+            &self.game.opponent_name,
+            layout[1],
+            buf,
+        );
+        if let Some(pause) = &self.game.pause {
+            let mut content = vec![Line::from(format!(
+                "{} paused the game, resuming in {}s",
+                self.game.pauser_name(pause.by),
+                pause.remaining().as_secs()
+            ))];
+            if !self.game.spectator {
+                content.push(Line::from("p. Resume".bold()));
+            }
+            print_pause(content, area, buf);
+        }
+        if self.game.forfeit_prompt.load(Ordering::Relaxed) {
+            let popup = centered_rect(area, 50, 5);
+            Clear.render(popup, buf);
+            Paragraph::new(vec![
+                Line::from("Really leave? You will lose".bold()),
+                Line::from(""),
+                Line::from("y. Yes   n. No".bold()),
+            ])
+            .centered()
+            .block(Block::bordered().border_set(border::THICK))
+            .render(popup, buf);
+        }
+    }
+    fn display_replay_screen(&self, area: Rect, buf: &mut Buffer) {
+        print_replay(&self.replay, area, buf);
+    }
+    fn display_live_games_screen(&self, area: Rect, buf: &mut Buffer) {
+        let visible = self.live_games.pager.visible(area.height.saturating_sub(3) as usize);
+        let min = visible.start;
+        let rows: Vec<Row> = self.live_games.games[visible]
+            .iter()
+            .map(|game| {
+                Row::new(vec![
+                    Span::raw(game.player1_name.as_str()),
+                    Span::raw(format!("{} - {}", game.player1_score, game.player2_score)),
+                    Span::raw(game.player2_name.as_str()),
+                ])
             })
-            .render(layout[0], buf);
-        let line = Line::from(vec![
-            format!("You: {}", self.game.game_stats.player1_score).bold(),
-            "    |     ".bold(),
-            format!(
-                "{}: {}",
-                self.game.opponent_name, self.game.game_stats.player2_score
-            )
-            .bold(),
+            .collect();
+        let instructions = Line::from(vec![
+            " Menu: ↑↓ Select ".bold(),
+            " ←→ Page ".bold(),
+            " Enter. Watch ".bold(),
+            " r. Refresh ".bold(),
+            " ESC. Back ".bold(),
         ]);
-        Paragraph::new(line)
-            .block(
-                Block::bordered()
-                    .border_set(border::THICK)
-                    .title("Score".bold()),
-            )
-            .centered()
-            .render(layout[1], buf);
+        let block = Block::bordered()
+            .title(Line::from("Live games").bold().centered())
+            .title_bottom(instructions.centered())
+            .border_set(border::THICK);
+        if self.live_games.games.is_empty() {
+            Paragraph::new(Line::from("No game is being played".bold()))
+                .centered()
+                .block(block)
+                .render(area, buf);
+            return;
+        }
+        let table = Table::new(
+            rows,
+            [
+                Constraint::Fill(1),
+                Constraint::Length(9),
+                Constraint::Fill(1),
+            ],
+        )
+        .header(Row::new(vec!["Left player", "Score", "Right player"]).bold())
+        .row_highlight_style(Style::default().add_modifier(Modifier::REVERSED))
+        .block(block);
+        let mut state =
+            TableState::default().with_selected(self.live_games.pager.selected.checked_sub(min));
+        StatefulWidget::render(table, area, buf, &mut state);
     }
     fn display_endgame(&self, area: Rect, buf: &mut Buffer) {
-        let sentence: &str = match self.game.game_stats.winner {
-            true => "You Win :)",
-            false => "You lose :(",
+        let game = &self.game;
+        let stats = &game.game_stats;
+        let sentence = match (game.spectator, game.end, stats.winner) {
+            (true, GameEnd::ConnectionLost, _) => "Connection lost".to_string(),
+            (true, _, true) => format!("{} wins", game.player_name),
+            (true, _, false) => format!("{} wins", game.opponent_name),
+            (false, GameEnd::OpponentLeft, _) => format!("{} left, you win :)", game.opponent_name),
+            (false, GameEnd::Forfeited, _) => "You forfeited".to_string(),
+            (false, GameEnd::Disconnected, _) => "You were disconnected".to_string(),
+            (false, GameEnd::ConnectionLost, _) => "Connection lost".to_string(),
+            (false, _, true) => "You Win :)".to_string(),
+            (false, _, false) => "You lose :(".to_string(),
         };
-        let block = Block::bordered().border_set(border::THICK);
-        let spanlist: Vec<Span> = vec![sentence.bold(), " Press Enter to Continue".bold()];
-        Paragraph::new(Line::from(spanlist))
+        let player = match game.spectator {
+            true => game.player_name.as_str(),
+            false => "You",
+        };
+        let loser = match stats.winner {
+            true => game.opponent_name.as_str(),
+            false => player,
+        };
+        let reason = match (game.spectator, game.end, game.outcome.reason) {
+            (false, GameEnd::Forfeited | GameEnd::Disconnected, _) => {
+                "The game counts as a loss".to_string()
+            }
+            (_, GameEnd::ConnectionLost, _) => "The game server stopped answering".to_string(),
+            (_, _, EndReason::Normal) => String::new(),
+            (_, _, EndReason::Forfeit) => format!("{loser} forfeited"),
+            (_, _, EndReason::Disconnect) => format!("{loser} lost the connection"),
+        };
+        let mut content = vec![
+            Line::from(sentence.bold()),
+            Line::from(reason.italic()),
+            Line::from(format!(
+                "{player} {} - {} {}",
+                stats.player1_score, stats.player2_score, game.opponent_name
+            )),
+            Line::from(format!("Duration: {}", game.printable_duration())),
+            Line::from(format!(
+                "Rallies: {} hits, longest point {} hits",
+                game.analytics.rallies.hits, game.analytics.rallies.longest
+            )),
+            Line::from(format!(
+                "Average ball speed: {:.1} units/s",
+                game.analytics.average_ball_speed()
+            )),
+            Line::from(format!(
+                "Time moving: {player} {:.0}s, {} {:.0}s",
+                game.analytics.moving.0, game.opponent_name, game.analytics.moving.1
+            )),
+        ];
+        if let Some(error) = &game.save_error {
+            let error = format!("Not saved in the history: {error}");
+            content.push(Line::from(error.fg(theme().negative)));
+        }
+        content.push(Line::from(""));
+        let rematch = !game.spectator && game.tournament.is_none();
+        if rematch {
+            let state = match game.rematch {
+                RematchState::None => None,
+                RematchState::Proposed => Some(format!("Waiting for {}...", game.opponent_name)),
+                RematchState::Received => Some(format!("{} wants a rematch", game.opponent_name)),
+                RematchState::Accepted | RematchState::Crossed => {
+                    Some(format!("{} accepted", game.opponent_name))
+                }
+                RematchState::Declined => Some(format!("{} declined", game.opponent_name)),
+                RematchState::Left => Some(format!("{} left", game.opponent_name)),
+            };
+            if let Some(state) = state {
+                content.push(Line::from(state.fg(theme().highlight)));
+            }
+        }
+        let instructions = match (rematch, game.rematch) {
+            (true, RematchState::Received) => " r. Accept  d. Decline  Enter. Continue ",
+            (true, RematchState::Proposed) => " Enter. Continue ",
+            (true, _) => " r. Rematch  Enter. Continue ",
+            _ => " Press Enter to Continue ",
+        };
+        let block = Block::bordered()
+            .title_bottom(Line::from(instructions.bold()).centered())
+            .border_set(border::THICK);
+        let height = content.len() as u16 + 2;
+        let vertical = Layout::vertical([Constraint::Length(height), Constraint::Fill(1)])
+            .split(area);
+        Paragraph::new(content)
             .centered()
             .block(block)
-            .render(area, buf);
+            .render(vertical[0], buf);
+        print_match_charts(&game.analytics, player, &game.opponent_name, vertical[1], buf);
     }
     fn display_signup_screen(&self, area: Rect, buf: &mut Buffer) {
         let mail = format!(
@@ -224,15 +362,15 @@ impl ScreenDisplayer for Infos {
             )),
             Line::from(""),
             Line::from(vec![
-                Span::styled("Email:     ", Style::default().fg(Color::Gray)),
+                Span::styled("Email:     ", Style::default().fg(theme().label)),
                 Span::raw(mail),
             ]),
             Line::from(vec![
-                Span::styled("Username:  ", Style::default().fg(Color::Gray)),
+                Span::styled("Username:  ", Style::default().fg(theme().label)),
                 Span::raw(username),
             ]),
             Line::from(vec![
-                Span::styled("Password:  ", Style::default().fg(Color::Gray)),
+                Span::styled("Password:  ", Style::default().fg(theme().label)),
                 Span::raw(password),
             ]),
         ];
@@ -274,15 +412,15 @@ impl ScreenDisplayer for Infos {
             )),
             Line::from(""),
             Line::from(vec![
-                Span::styled("Email:     ", Style::default().fg(Color::Gray)),
+                Span::styled("Email:     ", Style::default().fg(theme().label)),
                 Span::raw(mail),
             ]),
             Line::from(vec![
-                Span::styled("Password:  ", Style::default().fg(Color::Gray)),
+                Span::styled("Password:  ", Style::default().fg(theme().label)),
                 Span::raw(password),
             ]),
             Line::from(vec![
-                Span::styled("2FA Code:  ", Style::default().fg(Color::Gray)),
+                Span::styled("2FA Code:  ", Style::default().fg(theme().label)),
                 Span::raw(totp),
             ]),
         ];
@@ -313,92 +451,942 @@ impl ScreenDisplayer for Infos {
             self.friend.friend_tmp,
             if self.friend.blink { "|" } else { "" }
         );
-        let content = vec![
+        let mut content = vec![
             Line::from(Span::styled(
                 "Add a friend",
                 Style::default().add_modifier(Modifier::BOLD),
             )),
             Line::from(""),
             Line::from(vec![
-                Span::styled("Friend:     ", Style::default().fg(Color::Gray)),
+                Span::styled("Friend:     ", Style::default().fg(theme().label)),
                 Span::raw(friend),
             ]),
         ];
+        for (index, profile) in self.friend.suggestions.iter().enumerate() {
+            let style = match self.friend.suggestion_index == Some(index) {
+                true => Style::default().add_modifier(Modifier::REVERSED),
+                false => Style::default(),
+            };
+            content.push(Line::from(vec![
+                Span::raw("            "),
+                Span::styled("● ", Style::default().fg(profile.presence.color())),
+                Span::styled(profile.name.as_str(), style),
+            ]));
+        }
         Paragraph::new(content)
             .block(
                 Block::default()
                     .title("Add Friend".bold())
-                    .title_bottom("Menu: Enter. Ok  ESC. Quit ".bold().into_centered_line())
+                    .title_bottom(
+                        "Menu: ↑↓ Pick  Tab. Complete  Enter. Ok  ESC. Quit "
+                            .bold()
+                            .into_centered_line(),
+                    )
                     .borders(Borders::ALL),
             )
             .alignment(Alignment::Left)
             .render(area, buf);
     }
-    fn display_delete_friends_screen(&self, area: Rect, buf: &mut Buffer) {
-        let friend = format!(
-            "{}{}",
-            self.friend.friend_tmp,
-            if self.friend.blink { "|" } else { "" }
-        );
+    fn display_profile_screen(&self, area: Rect, buf: &mut Buffer) {
+        let layout = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints(vec![
+                Constraint::Length(PROFILE_HEADER_HEIGHT),
+                Constraint::Fill(1),
+            ])
+            .split(area);
+        let profile = &self.profile.profile;
+        let streak = match current_streak(&self.profile.history) {
+            (0, _) => "-".to_string(),
+            (count, true) => format!("{count} win(s)"),
+            (count, false) => format!("{count} loss(es)"),
+        };
         let content = vec![
             Line::from(Span::styled(
-                "Delete a friend",
+                profile.name.as_str(),
                 Style::default().add_modifier(Modifier::BOLD),
             )),
-            Line::from(""),
             Line::from(vec![
-                Span::styled("Friend:     ", Style::default().fg(Color::Gray)),
-                Span::raw(friend),
+                Span::styled("Id:        ", Style::default().fg(theme().label)),
+                Span::raw(profile.id.to_string()),
+            ]),
+            Line::from(vec![
+                Span::styled("Account:   ", Style::default().fg(theme().label)),
+                Span::raw(if profile.guest { "guest" } else { "registered" }),
+            ]),
+            Line::from(vec![
+                Span::styled("Status:    ", Style::default().fg(theme().label)),
+                Span::styled(
+                    profile.presence.label(),
+                    Style::default().fg(profile.presence.color()),
+                ),
+            ]),
+            Line::from(vec![
+                Span::styled("Record:    ", Style::default().fg(theme().label)),
+                Span::raw(format!(
+                    "{} W / {} L ({:.1}%)",
+                    profile.wins,
+                    profile.losses,
+                    profile.win_rate()
+                )),
+            ]),
+            Line::from(vec![
+                Span::styled("Streak:    ", Style::default().fg(theme().label)),
+                Span::raw(streak),
             ]),
         ];
         Paragraph::new(content)
             .block(
                 Block::default()
-                    .title("Delete friend".bold())
-                    .title_bottom("Menu: Enter. Ok  ESC. Quit ".bold().into_centered_line())
+                    .title("Profile".bold())
                     .borders(Borders::ALL),
             )
             .alignment(Alignment::Left)
+            .render(layout[0], buf);
+        let visible = self.profile.pager.visible(layout[1].height.saturating_sub(3) as usize);
+        let rows: Vec<Row> = self.profile.history[visible]
+            .iter()
+            .map(|record| {
+                let color = if record.won { theme().positive } else { theme().negative };
+                Row::new(vec![
+                    Span::styled(if record.won { "W" } else { "L" }, Style::default().fg(color)),
+                    Span::raw(record.opponent.as_str()),
+                    Span::raw(format!("{} - {}", record.score, record.opponent_score)),
+                    Span::raw(record.date.as_str()),
+                    Span::raw(record.printable_duration()),
+                ])
+            })
+            .collect();
+        let pager = &self.profile.pager;
+        let instructions = Line::from(vec![
+            format!(" Page {}/{} ", pager.index + 1, pager.index_max + 1).bold(),
+            " ←→ Page ".bold(),
+            " ESC. Back ".bold(),
+        ]);
+        let table = Table::new(
+            rows,
+            [
+                Constraint::Length(3),
+                Constraint::Fill(2),
+                Constraint::Length(9),
+                Constraint::Fill(2),
+                Constraint::Length(8),
+            ],
+        )
+        .header(Row::new(vec!["", "Opponent", "Score", "Date", "Duration"]).bold())
+        .block(
+            Block::bordered()
+                .title("Match history".bold())
+                .title_bottom(instructions.centered()),
+        );
+        Widget::render(table, layout[1], buf);
+    }
+    fn display_friend_requests_screen(&self, area: Rect, buf: &mut Buffer) {
+        let instructions = Line::from(vec![
+            " Menu: ↑↓ Select ".bold(),
+            " Enter. Accept ".bold(),
+            " d. Decline ".bold(),
+            " ESC. Back ".bold(),
+        ]);
+        let block = Block::bordered()
+            .title(Line::from("Friend Requests").bold().centered())
+            .title_bottom(instructions.centered())
+            .border_set(border::THICK);
+        let requests = self.friend.incoming_requests();
+        let lines: Vec<Line> = match requests.is_empty() {
+            true => vec![Line::from("No pending request".bold())],
+            false => requests
+                .iter()
+                .enumerate()
+                .map(|(index, friend)| match index == self.friend.requests_index {
+                    true => Line::from(format!("> {} <", friend.name).bold().reversed()),
+                    false => Line::from(friend.name.clone().bold()),
+                })
+                .collect(),
+        };
+        Paragraph::new(lines)
+            .centered()
+            .block(block)
             .render(area, buf);
     }
+    fn display_leaderboard_screen(&self, area: Rect, buf: &mut Buffer) {
+        let my_id = self.authent.borrow().id;
+        let friends: Vec<u64> = self
+            .friend
+            .friends_list
+            .iter()
+            .filter(|friend| friend.status == FriendStatus::Accepted)
+            .map(|friend| friend.id)
+            .collect();
+        let visible = self.leaderboard.pager.visible(area.height.saturating_sub(3) as usize);
+        let min = visible.start;
+        let rows: Vec<Row> = self.leaderboard.entries[visible.clone()]
+            .iter()
+            .zip(visible)
+            .map(|(entry, rank)| {
+                let profile = &entry.profile;
+                let style = if profile.id == my_id {
+                    theme().highlight_style().add_modifier(Modifier::BOLD)
+                } else if friends.contains(&profile.id) {
+                    Style::default().fg(theme().accent)
+                } else {
+                    Style::default()
+                };
+                Row::new(vec![
+                    format!("{}", rank + 1),
+                    profile.name.clone(),
+                    profile.wins.to_string(),
+                    profile.losses.to_string(),
+                    format!("{:.1}%", profile.win_rate()),
+                    entry.rating.to_string(),
+                ])
+                .style(style)
+            })
+            .collect();
+        let instructions = Line::from(vec![
+            " Menu: ↑↓ Select ".bold(),
+            " ←→ Page ".bold(),
+            format!(" s. Sort by {} ", self.leaderboard.ranking.label()).bold(),
+            " m. Me ".bold(),
+            " Enter. Profile ".bold(),
+            " ESC. Back ".bold(),
+        ]);
+        let table = Table::new(
+            rows,
+            [
+                Constraint::Length(5),
+                Constraint::Fill(2),
+                Constraint::Length(6),
+                Constraint::Length(6),
+                Constraint::Length(8),
+                Constraint::Length(7),
+            ],
+        )
+        .header(Row::new(vec!["#", "Player", "Wins", "Losses", "Ratio", "Rating"]).bold())
+        .row_highlight_style(Style::default().add_modifier(Modifier::REVERSED))
+        .block(
+            Block::bordered()
+                .title(Line::from("Leaderboard").bold().centered())
+                .title_bottom(instructions.centered())
+                .border_set(border::THICK),
+        );
+        let mut state =
+            TableState::default().with_selected(self.leaderboard.pager.selected.checked_sub(min));
+        StatefulWidget::render(table, area, buf, &mut state);
+    }
+    fn display_history_screen(&self, area: Rect, buf: &mut Buffer) {
+        let visible = self.history.pager.visible(area.height.saturating_sub(3) as usize);
+        let min = visible.start;
+        let rows: Vec<Row> = self.history.displayed[visible]
+            .iter()
+            .map(|record| {
+                let color = if record.won { theme().positive } else { theme().negative };
+                Row::new(vec![
+                    Span::styled(if record.won { "W" } else { "L" }, Style::default().fg(color)),
+                    Span::raw(record.opponent.as_str()),
+                    Span::raw(format!("{} - {}", record.score, record.opponent_score)),
+                    Span::raw(record.printable_date()),
+                    Span::raw(record.printable_duration()),
+                    Span::raw(record.server.as_str()),
+                ])
+            })
+            .collect();
+        let instructions = Line::from(vec![
+            " Menu: ↑↓ Select ".bold(),
+            " ←→ Page ".bold(),
+            " Enter. Replay ".bold(),
+            format!(" f. Showing {} ", self.history.result_filter.label()).bold(),
+            " ESC. Back ".bold(),
+        ]);
+        let table = Table::new(
+            rows,
+            [
+                Constraint::Length(3),
+                Constraint::Fill(2),
+                Constraint::Length(9),
+                Constraint::Length(18),
+                Constraint::Length(8),
+                Constraint::Fill(2),
+            ],
+        )
+        .header(Row::new(vec!["", "Opponent", "Score", "Date", "Duration", "Server"]).bold())
+        .row_highlight_style(Style::default().add_modifier(Modifier::REVERSED))
+        .block(
+            Block::bordered()
+                .title(Line::from("My history").bold().centered())
+                .title_bottom(instructions.centered())
+                .border_set(border::THICK),
+        );
+        let mut state =
+            TableState::default().with_selected(self.history.pager.selected.checked_sub(min));
+        StatefulWidget::render(table, area, buf, &mut state);
+    }
+    fn display_tournaments_screen(&self, area: Rect, buf: &mut Buffer) {
+        let visible = self.tournaments.pager.visible(area.height.saturating_sub(3) as usize);
+        let min = visible.start;
+        let my_id = self.authent.borrow().id;
+        let rows: Vec<Row> = self.tournaments.tournaments[visible]
+            .iter()
+            .map(|tournament| {
+                let joined = tournament.players.contains(&my_id);
+                let row = Row::new(vec![
+                    Span::raw(tournament.name.as_str()),
+                    Span::raw(format!("{}/{}", tournament.players.len(), tournament.size)),
+                    Span::raw(tournament.status.label()),
+                    Span::raw(if joined { "joined" } else { "" }),
+                ]);
+                match joined {
+                    true => row.style(theme().highlight_style()),
+                    false => row,
+                }
+            })
+            .collect();
+        let instructions = Line::from(vec![
+            " Menu: ↑↓ Select ".bold(),
+            " Enter. Join ".bold(),
+            " c. Create ".bold(),
+            " r. Refresh ".bold(),
+            " ESC. Back ".bold(),
+        ]);
+        let table = Table::new(
+            rows,
+            [
+                Constraint::Fill(2),
+                Constraint::Length(9),
+                Constraint::Length(20),
+                Constraint::Length(8),
+            ],
+        )
+        .header(Row::new(vec!["Tournament", "Players", "Status", ""]).bold())
+        .row_highlight_style(Style::default().add_modifier(Modifier::REVERSED))
+        .block(
+            Block::bordered()
+                .title(Line::from("Tournaments").bold().centered())
+                .title_bottom(instructions.centered())
+                .border_set(border::THICK),
+        );
+        let mut state =
+            TableState::default().with_selected(self.tournaments.pager.selected.checked_sub(min));
+        StatefulWidget::render(table, area, buf, &mut state);
+        if let Some(form) = &self.tournaments.form {
+            let popup = centered_rect(area, 50, 7);
+            Clear.render(popup, buf);
+            let sizes: Vec<Span> = TOURNAMENT_SIZES
+                .iter()
+                .map(|size| match *size == form.size() {
+                    true => format!(" {size} ").reversed(),
+                    false => format!(" {size} ").into(),
+                })
+                .collect();
+            Paragraph::new(vec![
+                Line::from(format!("Name: {}|", form.name).bold()),
+                Line::from(""),
+                Line::from([vec!["Players: ".bold()], sizes].concat()),
+                Line::from(""),
+                Line::from("←→ Size   Enter. Create   ESC. Cancel".bold()),
+            ])
+            .centered()
+            .block(
+                Block::bordered()
+                    .title("New tournament".bold())
+                    .border_set(border::THICK),
+            )
+            .render(popup, buf);
+        }
+    }
+    fn display_bracket_screen(&self, area: Rect, buf: &mut Buffer) {
+        let tournament = &self.bracket.tournament;
+        let my_id = self.authent.borrow().id;
+        let status = match (tournament.status, tournament.champion()) {
+            (_, Some(champion)) => format!("Champion: {}", tournament.name(champion)),
+            (TournamentStatus::Waiting, _) => format!(
+                "{} ({}/{} players)",
+                tournament.status.label(),
+                tournament.players.len(),
+                tournament.size
+            ),
+            (status, _) => status.label().to_string(),
+        };
+        let instructions = Line::from(vec![" ESC. Back ".bold()]);
+        let block = Block::bordered()
+            .title(Line::from(format!(" {} - {} ", tournament.name, status)).bold().centered())
+            .title_bottom(instructions.centered())
+            .border_set(border::THICK);
+        let inner = block.inner(area);
+        block.render(area, buf);
+        print_bracket(tournament, my_id, inner, buf);
+        if let Some((ready, notified)) = &self.bracket.ready {
+            let opponent = match ready.opponent_of(my_id) {
+                Some(Some(opponent)) => tournament.name(opponent),
+                _ => "?".to_string(),
+            };
+            let remaining = MATCH_COUNTDOWN.saturating_sub(notified.elapsed()).as_secs() + 1;
+            let popup = centered_rect(area, 50, 5);
+            Clear.render(popup, buf);
+            Paragraph::new(vec![
+                Line::from(format!("Your match against {opponent} is ready!").bold()),
+                Line::from(""),
+                Line::from(format!("Starting in {remaining}s, Enter to start now").bold()),
+            ])
+            .centered()
+            .block(
+                Block::bordered()
+                    .border_set(border::THICK)
+                    .border_style(Style::default().fg(theme().highlight)),
+            )
+            .render(popup, buf);
+        }
+    }
+    fn display_local_setup_screen(&self, area: Rect, buf: &mut Buffer) {
+        let setup = &self.local.setup;
+        let kind = |bot: bool| if bot { "Bot" } else { "Human" };
+        let mut lines = vec![
+            Line::from(format!("Format: ← {} →", setup.format.label()).bold()),
+            Line::from(format!("Next player: {} (Tab)", kind(setup.bot)).bold()),
+            Line::from(format!("Name: {}|", setup.name).bold()),
+            Line::from(""),
+        ];
+        lines.extend(setup.players.iter().enumerate().map(|(index, player)| {
+            Line::from(format!("{}. {} ({})", index + 1, player.name, kind(player.bot)))
+        }));
+        let instructions = Line::from(vec![
+            " Enter. Add player, empty name to start ".bold(),
+            " Backspace. Remove ".bold(),
+            " ESC. Back ".bold(),
+        ]);
+        Paragraph::new(lines)
+            .block(
+                Block::bordered()
+                    .title(Line::from("Local tournament").bold().centered())
+                    .title_bottom(instructions.centered())
+                    .border_set(border::THICK),
+            )
+            .render(area, buf);
+    }
+    fn display_local_standings_screen(&self, area: Rect, buf: &mut Buffer) {
+        let Some(tournament) = &self.local.tournament else {
+            return;
+        };
+        let title = match tournament.champion() {
+            Some(champion) => format!(
+                " {} - Champion: {} ",
+                tournament.format.label(),
+                tournament.players[champion].name
+            ),
+            _ => format!(" {} ", tournament.format.label()),
+        };
+        let next = match tournament.next_fixture() {
+            Some(_) => " Enter. Play next match ",
+            _ => "",
+        };
+        let mut instructions = vec![next.bold(), " ESC. New tournament ".bold()];
+        if let Some(result) = &self.local.last_result {
+            instructions.insert(0, format!(" Last: {result} ").into());
+        }
+        let block = Block::bordered()
+            .title(Line::from(title).bold().centered())
+            .title_bottom(Line::from(instructions).centered())
+            .border_set(border::THICK);
+        let inner = block.inner(area);
+        block.render(area, buf);
+        let layout = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints(vec![Constraint::Fill(1), Constraint::Fill(1)])
+            .split(inner);
+        print_local_standings(tournament, layout[0], buf);
+        print_local_fixtures(tournament, layout[1], buf);
+    }
+    fn display_local_play_screen(&self, area: Rect, buf: &mut Buffer) {
+        let (Some(playing), Some((left, right))) =
+            (&self.local.playing, self.local.current_players())
+        else {
+            return;
+        };
+        let layout = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints(vec![Constraint::Fill(1), Constraint::Max(3)])
+            .split(area);
+        let title = match (left.bot, right.bot) {
+            (false, false) => "Local game - left: W/S, right: ↑/↓, p. Pause, ESC. Give up",
+            _ => "Local game - ↑/↓ or W/S, p. Pause, ESC. Give up",
+        };
+        print_game(
+            &playing.simulation.stats,
+            &playing.simulation.config,
+            title,
+            layout[0],
+            buf,
+        );
+        print_score(
+            &playing.simulation.stats,
+            &left.name,
+            &right.name,
+            layout[1],
+            buf,
+        );
+        if playing.paused {
+            print_pause(vec![Line::from("p. Resume".bold())], area, buf);
+        }
+    }
     fn print_demo(&self, area: Rect, buf: &mut Buffer) {
         Canvas::default()
             .block(Block::bordered())
-            .marker(Marker::Braille)
+            .marker(court::marker())
             .x_bounds([0.0, 100.0])
             .y_bounds([0.0, 100.0])
-            .paint(|ctx| {
-                ctx.draw(&Circle {
-                    x: self.demo.ball_x,
-                    y: self.demo.ball_y,
-                    radius: 0.5,
-                    color: Color::Yellow,
-                });
-                ctx.draw(&Rectangle {
-                    x: 1.5,
-                    y: self.demo.paddle_left_y,
-                    width: 2.0,
-                    height: 10.0,
-                    color: Color::Green,
-                });
-                ctx.draw(&Rectangle {
-                    x: 95.0,
-                    y: self.demo.paddle_right_y,
-                    width: 2.0,
-                    height: 10.0,
-                    color: Color::Green,
-                });
-            })
+            .paint(|ctx| paint_court(ctx, &self.demo.stats(), &self.demo.config))
             .render(area, buf);
     }
 }
 
-fn print_block(instructions: Line, area: Rect, buf: &mut Buffer) {
+///Draw the Replay screen
+pub(crate) fn print_replay(replay: &ReplayPlayer, area: Rect, buf: &mut Buffer) {
+    let layout = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints(vec![Constraint::Fill(1), Constraint::Max(3), Constraint::Max(3)])
+        .split(area);
+    let stats = replay.stats();
+    print_game(&stats, &CourtConfig::default(), "Replay", layout[0], buf);
+    print_score(&stats, "You", &replay.opponent, layout[1], buf);
+    let printable = |ms: u64| format!("{}:{:02}", ms / 60000, ms / 1000 % 60);
+    let status = Line::from(vec![
+        if replay.paused { " Paused " } else { " Playing " }.bold(),
+        format!(
+            " {} / {} ",
+            printable(replay.position as u64),
+            printable(replay.duration())
+        )
+        .into(),
+        format!(" x{} ", replay.speed_factor()).bold(),
+        format!(" frame {}/{} ", replay.current + 1, replay.frames.len()).into(),
+    ]);
+    let instructions = Line::from(vec![
+        " Space. Pause ".bold(),
+        " ←→ Seek ".bold(),
+        " ↑↓ Speed ".bold(),
+        " ,. Step ".bold(),
+        " ESC. Back ".bold(),
+    ]);
+    Paragraph::new(status)
+        .block(
+            Block::bordered()
+                .border_set(border::THICK)
+                .title_bottom(instructions.centered()),
+        )
+        .centered()
+        .render(layout[2], buf);
+}
+
+///Draw the ranking of the players of a local tournament
+fn print_local_standings(tournament: &LocalTournament, area: Rect, buf: &mut Buffer) {
+    let rows: Vec<Row> = tournament
+        .standings()
+        .iter()
+        .enumerate()
+        .map(|(rank, standing)| {
+            Row::new(vec![
+                format!("{}", rank + 1),
+                tournament.players[standing.player].name.clone(),
+                standing.played.to_string(),
+                standing.wins.to_string(),
+                format!("{:+}", standing.scored as i64 - standing.conceded as i64),
+            ])
+        })
+        .collect();
+    let table = Table::new(
+        rows,
+        [
+            Constraint::Length(3),
+            Constraint::Fill(1),
+            Constraint::Length(6),
+            Constraint::Length(5),
+            Constraint::Length(5),
+        ],
+    )
+    .header(Row::new(vec!["#", "Player", "Games", "Wins", "+/-"]).bold())
+    .block(Block::bordered().title("Standings".bold()));
+    Widget::render(table, area, buf);
+}
+
+///Draw the matches of a local tournament, the next one highlighted
+fn print_local_fixtures(tournament: &LocalTournament, area: Rect, buf: &mut Buffer) {
+    let next = tournament.next_fixture();
+    let rows: Vec<Row> = tournament
+        .fixtures
+        .iter()
+        .enumerate()
+        .map(|(index, fixture)| {
+            let right = match fixture.right {
+                Some(right) => tournament.players[right].name.clone(),
+                _ => "(bye)".to_string(),
+            };
+            let score = match fixture.result {
+                Some((left_score, right_score)) => format!("{left_score} - {right_score}"),
+                _ => "-".to_string(),
+            };
+            let row = Row::new(vec![
+                format!("{}", fixture.round + 1),
+                tournament.players[fixture.left].name.clone(),
+                score,
+                right,
+            ]);
+            match Some(index) == next {
+                true => row.style(theme().highlight_style().bold()),
+                false => row,
+            }
+        })
+        .collect();
+    let table = Table::new(
+        rows,
+        [
+            Constraint::Length(5),
+            Constraint::Fill(1),
+            Constraint::Length(7),
+            Constraint::Fill(1),
+        ],
+    )
+    .header(Row::new(vec!["Round", "Left", "Score", "Right"]).bold())
+    .block(Block::bordered().title("Matches".bold()));
+    Widget::render(table, area, buf);
+}
+
+///Draw the bracket tree, one column per round
+fn print_bracket(tournament: &Tournament, my_id: u64, area: Rect, buf: &mut Buffer) {
+    let rounds = tournament.rounds();
+    let columns = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints(vec![Constraint::Fill(1); rounds])
+        .split(area);
+    for (round, column) in columns.iter().enumerate() {
+        let label = match rounds - round {
+            1 => "Final".to_string(),
+            2 => "Semi-finals".to_string(),
+            3 => "Quarter-finals".to_string(),
+            _ => format!("Round {}", round + 1),
+        };
+        Paragraph::new(Line::from(label).bold().centered()).render(
+            Rect {
+                height: 1.min(column.height),
+                ..*column
+            },
+            buf,
+        );
+        let count = (tournament.size.max(2).next_power_of_two() >> (round + 1)) as u16;
+        let slots = Rect {
+            y: column.y + 1,
+            height: column.height.saturating_sub(1),
+            ..*column
+        };
+        for position in 0..count {
+            let top = slots.y + slots.height * position / count;
+            let slot_height = slots.y + slots.height * (position + 1) / count - top;
+            let height = slot_height.min(4);
+            let slot = Rect {
+                x: column.x + 1,
+                y: top + (slot_height - height) / 2,
+                width: column.width.saturating_sub(2),
+                height,
+            };
+            print_bracket_match(
+                tournament,
+                tournament.get_match(round, position as usize),
+                my_id,
+                slot,
+                buf,
+            );
+        }
+    }
+}
+
+///Draw a match of the bracket, with a border if there is room for it
+fn print_bracket_match(
+    tournament: &Tournament,
+    bracket_match: Option<&BracketMatch>,
+    my_id: u64,
+    area: Rect,
+    buf: &mut Buffer,
+) {
+    let player_line = |player: Option<u64>, score: u64| {
+        let (name, style) = match player {
+            Some(id) => {
+                let style = match bracket_match.and_then(|played| played.winner) {
+                    Some(winner) if winner == id => Style::default().fg(theme().positive).bold(),
+                    Some(_) => Style::default().fg(theme().muted),
+                    _ if id == my_id => theme().highlight_style(),
+                    _ => Style::default(),
+                };
+                (tournament.name(id), style)
+            }
+            _ => ("?".to_string(), Style::default().fg(theme().muted)),
+        };
+        let score = match bracket_match.and_then(|played| played.winner) {
+            Some(_) => format!(" {score}"),
+            _ => String::new(),
+        };
+        Line::from(vec![Span::styled(name, style), Span::raw(score)])
+    };
+    let lines = match bracket_match {
+        Some(played) => vec![
+            player_line(played.player1, played.score1),
+            player_line(played.player2, played.score2),
+        ],
+        _ => vec![player_line(None, 0), player_line(None, 0)],
+    };
+    match area.height {
+        0 => {}
+        1 => Paragraph::new(Line::from(
+            [lines[0].spans.clone(), vec![" - ".into()], lines[1].spans.clone()].concat(),
+        ))
+        .render(area, buf),
+        2 | 3 => Paragraph::new(lines).render(area, buf),
+        _ => Paragraph::new(lines)
+            .block(Block::bordered())
+            .render(area, buf),
+    }
+}
+
+///Draw the ball and the paddles of a game
+fn print_game(
+    stats: &GameStats,
+    config: &CourtConfig,
+    title: &str,
+    area: Rect,
+    buf: &mut Buffer,
+) {
+    if !court::fits(area) {
+        Paragraph::new(vec![
+            Line::from("Please enlarge your terminal".bold()),
+            Line::from(format!(
+                "The game needs at least {}x{} cells",
+                court::MIN_WIDTH,
+                court::MIN_HEIGHT
+            )),
+        ])
+        .centered()
+        .wrap(Wrap { trim: true })
+        .block(Block::bordered().title(title.bold()))
+        .render(area, buf);
+        return;
+    }
+    Canvas::default()
+        .block(Block::bordered().title(title.bold()))
+        .marker(court::marker())
+        .x_bounds([0.0, 100.0])
+        .y_bounds([0.0, 100.0])
+        .paint(|ctx| paint_court(ctx, stats, config))
+        .render(court::letterbox(area, config), buf);
+}
+
+///Draw the ball and the paddles, turning the server's y axis upward
+fn paint_court(ctx: &mut Context, stats: &GameStats, config: &CourtConfig) {
+    ctx.draw(&Circle {
+        x: stats.ball_x as f64,
+        y: 100.0 - stats.ball_y as f64,
+        radius: config.ball_radius,
+        color: theme().ball,
+    });
+    for (x, y) in [
+        (config.left_paddle_x(), stats.left_y),
+        (config.right_paddle_x(), stats.right_y),
+    ] {
+        ctx.draw(&Rectangle {
+            x,
+            y: 100.0 - y as f64 - config.paddle_height / 2.0,
+            width: config.paddle_width,
+            height: config.paddle_height,
+            color: theme().paddle,
+        });
+    }
+}
+
+///Points won per minute by each player, and hits of each point
+fn print_match_charts(
+    analytics: &MatchAnalytics,
+    player: &str,
+    opponent: &str,
+    area: Rect,
+    buf: &mut Buffer,
+) {
+    let layout = Layout::horizontal([Constraint::Fill(1), Constraint::Fill(1)]).split(area);
+    let groups: Vec<BarGroup> = analytics
+        .points_per_minute()
+        .iter()
+        .enumerate()
+        .map(|(minute, (left, right))| {
+            BarGroup::default()
+                .label(Line::from(format!("{}'", minute + 1)))
+                .bars(&[
+                    Bar::default().value(*left).style(Style::default().fg(theme().positive)),
+                    Bar::default().value(*right).style(Style::default().fg(theme().negative)),
+                ])
+        })
+        .collect();
+    let title = Line::from(vec![
+        " Points per minute: ".bold(),
+        player.fg(theme().positive).bold(),
+        " / ".bold(),
+        opponent.fg(theme().negative).bold(),
+        " ".into(),
+    ]);
+    let mut chart = BarChart::default()
+        .block(Block::bordered().border_set(border::THICK).title(title))
+        .bar_width(3)
+        .group_gap(2);
+    for group in groups {
+        chart = chart.data(group);
+    }
+    chart.render(layout[0], buf);
+    Sparkline::default()
+        .block(
+            Block::bordered()
+                .border_set(border::THICK)
+                .title(" Hits per point ".bold()),
+        )
+        .data(&analytics.rallies.per_point)
+        .style(Style::default().fg(theme().highlight))
+        .render(layout[1], buf);
+}
+
+///Overlay displayed over a paused game
+fn print_pause(mut content: Vec<Line>, area: Rect, buf: &mut Buffer) {
+    content.insert(0, Line::from("Paused".bold().fg(theme().highlight)));
+    content.insert(1, Line::from(""));
+    let popup = centered_rect(area, 50, content.len() as u16 + 2);
+    Clear.render(popup, buf);
+    Paragraph::new(content)
+        .centered()
+        .block(Block::bordered().border_set(border::THICK))
+        .render(popup, buf);
+}
+
+///Popup listing the key bindings of the screen and of its popup, then those of every screen
+pub(crate) fn print_help(screen: CurrentScreen, area: Rect, buf: &mut Buffer) {
+    let keys: Vec<(String, &str)> = keymap::bindings(screen)
+        .iter()
+        .map(|binding| (binding.keys_label(), binding.1))
+        .collect();
+    let popup_keys: Vec<(String, &str)> = keymap::popup_bindings(screen)
+        .map(|(_, bindings)| bindings.iter().map(|binding| (binding.keys_label(), binding.1)))
+        .into_iter()
+        .flatten()
+        .collect();
+    let key_width = keys
+        .iter()
+        .chain(&popup_keys)
+        .map(|(label, _)| label.chars().count())
+        .chain(keymap::GLOBAL_BINDINGS.iter().map(|(label, _)| label.chars().count()))
+        .max()
+        .unwrap_or(0);
+    let mut rows: Vec<Row> = keys
+        .iter()
+        .map(|(label, action)| Row::new(vec![label.clone().bold(), action.to_string().into()]))
+        .collect();
+    if let Some((title, _)) = keymap::popup_bindings(screen) {
+        rows.push(Row::new(vec![""; 2]));
+        rows.push(Row::new(vec!["".into(), title.fg(theme().highlight)]));
+        rows.extend(popup_keys.iter().map(|(label, action)| {
+            Row::new(vec![label.clone().bold(), action.to_string().into()])
+        }));
+    }
+    rows.push(Row::new(vec![""; 2]));
+    rows.extend(keymap::GLOBAL_BINDINGS.iter().map(|(label, action)| {
+        Row::new(vec![label.fg(theme().muted), action.fg(theme().muted)])
+    }));
+    let height = rows.len() + 2;
+    let popup = centered_rect(area, 76, height as u16);
+    Clear.render(popup, buf);
+    let table = Table::new(rows, [Constraint::Length(key_width as u16), Constraint::Fill(1)])
+        .column_spacing(2)
+        .block(
+            Block::bordered()
+                .border_set(border::THICK)
+                .title(Line::from(" Help ".bold().fg(theme().highlight)).centered())
+                .title_bottom(Line::from(" Any key to close ").centered()),
+        );
+    Widget::render(table, popup, buf);
+}
+
+fn print_score(stats: &GameStats, player: &str, opponent: &str, area: Rect, buf: &mut Buffer) {
+    let line = Line::from(vec![
+        format!("{}: {}", player, stats.player1_score).bold(),
+        "    |     ".bold(),
+        format!("{}: {}", opponent, stats.player2_score).bold(),
+    ]);
+    Paragraph::new(line)
+        .block(
+            Block::bordered()
+                .border_set(border::THICK)
+                .title("Score".bold()),
+        )
+        .centered()
+        .render(area, buf);
+}
+
+///Logo over the entries of a menu, the highlighted one activated with Enter
+///
+/// #Parameters
+/// - selected: index of the highlighted entry
+fn print_menu(title: &str, entries: &[MenuAction], selected: usize, area: Rect, buf: &mut Buffer) {
+    let instructions = Line::from(vec![
+        " ↑↓ Select ".bold(),
+        " Enter. Open ".bold(),
+        format!(" 1-{}. Shortcut ", entries.len()).bold(),
+        match entries.contains(&MenuAction::Quit) {
+            true => " ESC. Quit ".bold(),
+            false => " ESC. Back ".bold(),
+        },
+    ]);
+    let bottom = area.bottom().saturating_sub(1);
+    mouse::add_title_targets(&instructions, area, bottom, &[None, None, None, Some(KeyCode::Esc)]);
     let block = Block::bordered()
+        .title(Line::from(format!(" {title} ")).bold().centered())
         .title_bottom(instructions.centered())
         .border_set(border::THICK);
-    Paragraph::new(LOGO)
-        .centered()
-        .block(block)
-        .render(area, buf);
+    let inner = block.inner(area);
+    block.render(area, buf);
+    let layout = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints(vec![Constraint::Length(LOGO_HEIGHT), Constraint::Fill(1)])
+        .split(inner);
+    Paragraph::new(LOGO).centered().render(layout[0], buf);
+    let width = entries
+        .iter()
+        .map(|entry| entry.label().len() as u16 + 5)
+        .max()
+        .unwrap_or(0);
+    let list_area = Rect {
+        x: layout[1].x + layout[1].width.saturating_sub(width) / 2,
+        width: width.min(layout[1].width),
+        ..layout[1]
+    };
+    let items: Vec<ListItem> = entries
+        .iter()
+        .enumerate()
+        .map(|(index, entry)| ListItem::new(format!("{}. {}", index + 1, entry.label())))
+        .collect();
+    for row in 0..items.len().min(list_area.height as usize) {
+        let row_area = Rect::new(list_area.x, list_area.y + row as u16, list_area.width, 1);
+        mouse::add_target(row_area, Target::Row(row));
+    }
+    let mut state = ListState::default().with_selected(Some(selected));
+    StatefulWidget::render(
+        List::new(items)
+            .highlight_style(theme().highlight_style().add_modifier(Modifier::REVERSED))
+            .highlight_symbol("> "),
+        list_area,
+        buf,
+        &mut state,
+    );
+}
+
+///Area of the given size centered in `area`, clamped to fit
+fn centered_rect(area: Rect, width: u16, height: u16) -> Rect {
+    let width = width.min(area.width);
+    let height = height.min(area.height);
+    Rect {
+        x: area.x + (area.width - width) / 2,
+        y: area.y + (area.height - height) / 2,
+        width,
+        height,
+    }
 }
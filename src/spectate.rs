@@ -0,0 +1,86 @@
+use crate::Context;
+use crate::court::CourtConfig;
+use crate::directory::get_users;
+use crate::pager::Pager;
+use anyhow::{Result, anyhow};
+use std::rc::Rc;
+
+///A game being played on the server
+pub(crate) struct LiveGame {
+    pub(crate) id: String,
+    pub(crate) player1_id: u64,
+    pub(crate) player2_id: u64,
+    pub(crate) player1_name: String,
+    pub(crate) player2_name: String,
+    pub(crate) player1_score: u64,
+    pub(crate) player2_score: u64,
+    pub(crate) config: CourtConfig,
+}
+
+impl LiveGame {
+    fn from_value(value: &serde_json::Value) -> Option<LiveGame> {
+        Some(LiveGame {
+            id: value["gameId"].as_str()?.to_string(),
+            player1_id: value["player1Id"].as_u64()?,
+            player2_id: value["player2Id"].as_u64()?,
+            player1_name: String::new(),
+            player2_name: String::new(),
+            player1_score: value["player1Score"].as_u64().unwrap_or_default(),
+            player2_score: value["player2Score"].as_u64().unwrap_or_default(),
+            config: CourtConfig::from_value(&value["config"]),
+        })
+    }
+}
+
+///State of the Live games screen
+#[derive(Default)]
+pub(crate) struct LiveGames {
+    pub(crate) games: Vec<LiveGame>,
+    pub(crate) pager: Pager,
+}
+
+impl LiveGames {
+    ///Fetch the games currently played on the server
+    ///
+    /// #Errors
+    /// Returns an error if the server does not send a list of games
+    pub(crate) async fn fetch(context: Rc<Context>) -> Result<LiveGames> {
+        let apiloc = format!("https://{}/api/games/running", context.location);
+        let response = context.client.get(apiloc).send().await?;
+        let response: serde_json::Value = response.json().await?;
+        let mut games: Vec<LiveGame> = match response.as_array() {
+            Some(games) => games.iter().filter_map(LiveGame::from_value).collect(),
+            _ => match response["message"].as_str() {
+                Some(message) => return Err(anyhow!(message.to_string())),
+                _ => return Err(anyhow!("Running games unavailable")),
+            },
+        };
+        let ids: Vec<u64> = games
+            .iter()
+            .flat_map(|game| [game.player1_id, game.player2_id])
+            .collect();
+        let players = get_users(context, &ids).await;
+        let name = |id: u64| match players.get(&id) {
+            Some(profile) => profile.name.clone(),
+            _ => format!("#{id}"),
+        };
+        for game in &mut games {
+            game.player1_name = name(game.player1_id);
+            game.player2_name = name(game.player2_id);
+        }
+        let mut live_games = LiveGames {
+            games,
+            ..Default::default()
+        };
+        live_games.pager.set_len(live_games.games.len());
+        Ok(live_games)
+    }
+    pub(crate) fn selected_game(&self) -> Option<&LiveGame> {
+        self.games.get(self.pager.selected)
+    }
+    ///Update game pages depending on the terminal's size
+    pub(crate) fn update_index(&mut self, terminal: &mut ratatui::DefaultTerminal) {
+        let page_height = terminal.get_frame().area().height.saturating_sub(3) as usize;
+        self.pager.update(self.games.len(), page_height);
+    }
+}
@@ -0,0 +1,196 @@
+use anyhow::{Result, anyhow};
+use ratatui::style::{Color, Modifier, Style};
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::sync::OnceLock;
+
+///Names of the built-in themes
+pub(crate) const PRESETS: [&str; 5] = [
+    "classic",
+    "solarized",
+    "monochrome",
+    "high-contrast",
+    "colorblind",
+];
+
+static THEME: OnceLock<Theme> = OnceLock::new();
+
+///Colors used by every screen
+#[derive(Clone, Copy)]
+pub(crate) struct Theme {
+    pub(crate) ball: Color,
+    pub(crate) paddle: Color,
+    ///Field names of forms and profiles
+    pub(crate) label: Color,
+    ///Wins, available friends and the player's side of charts
+    pub(crate) positive: Color,
+    ///Losses and the opponent's side of charts
+    pub(crate) negative: Color,
+    ///Selected rows, the player's own entries and notices
+    pub(crate) highlight: Color,
+    pub(crate) accent: Color,
+    ///Friends playing a game
+    pub(crate) special: Color,
+    ///Eliminated players and offline friends
+    pub(crate) muted: Color,
+    ///Added to highlighted text, for themes where colors alone do not stand out
+    emphasis: Modifier,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Theme {
+            ball: Color::Yellow,
+            paddle: Color::Green,
+            label: Color::Gray,
+            positive: Color::Green,
+            negative: Color::Red,
+            highlight: Color::Yellow,
+            accent: Color::Cyan,
+            special: Color::Magenta,
+            muted: Color::DarkGray,
+            emphasis: Modifier::empty(),
+        }
+    }
+}
+
+impl Theme {
+    pub(crate) fn preset(name: &str) -> Option<Theme> {
+        let theme = match name {
+            "classic" => Theme::default(),
+            "solarized" => Theme {
+                ball: Color::Rgb(0xb5, 0x89, 0x00),
+                paddle: Color::Rgb(0x2a, 0xa1, 0x98),
+                label: Color::Rgb(0x93, 0xa1, 0xa1),
+                positive: Color::Rgb(0x85, 0x99, 0x00),
+                negative: Color::Rgb(0xdc, 0x32, 0x2f),
+                highlight: Color::Rgb(0xb5, 0x89, 0x00),
+                accent: Color::Rgb(0x26, 0x8b, 0xd2),
+                special: Color::Rgb(0xd3, 0x36, 0x82),
+                muted: Color::Rgb(0x58, 0x6e, 0x75),
+                emphasis: Modifier::empty(),
+            },
+            "monochrome" => Theme {
+                ball: Color::Reset,
+                paddle: Color::Reset,
+                label: Color::Reset,
+                positive: Color::Reset,
+                negative: Color::Reset,
+                highlight: Color::Reset,
+                accent: Color::Reset,
+                special: Color::Reset,
+                muted: Color::Reset,
+                emphasis: Modifier::BOLD | Modifier::UNDERLINED,
+            },
+            "high-contrast" => Theme {
+                ball: Color::White,
+                paddle: Color::LightCyan,
+                label: Color::White,
+                positive: Color::LightGreen,
+                negative: Color::LightRed,
+                highlight: Color::LightYellow,
+                accent: Color::LightCyan,
+                special: Color::LightMagenta,
+                muted: Color::Gray,
+                emphasis: Modifier::BOLD,
+            },
+            //Okabe-Ito palette, told apart with any color vision deficiency
+            "colorblind" => Theme {
+                ball: Color::Rgb(0xf0, 0xe4, 0x42),
+                paddle: Color::Rgb(0x56, 0xb4, 0xe9),
+                label: Color::Gray,
+                positive: Color::Rgb(0x00, 0x72, 0xb2),
+                negative: Color::Rgb(0xe6, 0x9f, 0x00),
+                highlight: Color::Rgb(0xf0, 0xe4, 0x42),
+                accent: Color::Rgb(0x56, 0xb4, 0xe9),
+                special: Color::Rgb(0xcc, 0x79, 0xa7),
+                muted: Color::DarkGray,
+                emphasis: Modifier::empty(),
+            },
+            _ => return None,
+        };
+        Some(theme)
+    }
+    ///Style of selected rows and of the player's own entries
+    pub(crate) fn highlight_style(&self) -> Style {
+        Style::default()
+            .fg(self.highlight)
+            .add_modifier(self.emphasis)
+    }
+    ///Replace the colors named in a theme file
+    fn apply_colors(&mut self, colors: &serde_json::Value) -> Result<()> {
+        let Some(colors) = colors.as_object() else {
+            return Ok(());
+        };
+        for (name, value) in colors {
+            let color = value
+                .as_str()
+                .and_then(|value| Color::from_str(value).ok())
+                .ok_or_else(|| anyhow!("invalid color for {name}: {value}"))?;
+            let slot = match name.as_str() {
+                "ball" => &mut self.ball,
+                "paddle" => &mut self.paddle,
+                "label" => &mut self.label,
+                "positive" => &mut self.positive,
+                "negative" => &mut self.negative,
+                "highlight" => &mut self.highlight,
+                "accent" => &mut self.accent,
+                "special" => &mut self.special,
+                "muted" => &mut self.muted,
+                _ => return Err(anyhow!("unknown theme color {name}")),
+            };
+            *slot = color;
+        }
+        Ok(())
+    }
+}
+
+///Theme chosen at startup, the classic one if none was loaded
+pub(crate) fn theme() -> &'static Theme {
+    THEME.get_or_init(Theme::default)
+}
+
+///Path of the theme file: PONG_CONFIG_DIR, else the XDG config directory
+fn theme_path() -> Option<PathBuf> {
+    if let Ok(dir) = std::env::var("PONG_CONFIG_DIR") {
+        return Some(PathBuf::from(dir).join("theme.json"));
+    }
+    if let Ok(dir) = std::env::var("XDG_CONFIG_HOME") {
+        return Some(PathBuf::from(dir).join("pong-cli/theme.json"));
+    }
+    let home = std::env::var("HOME").ok()?;
+    Some(PathBuf::from(home).join(".config/pong-cli/theme.json"))
+}
+
+///Load the theme of the session
+///
+/// The theme file names a preset and may replace some of its colors:
+/// `{"preset": "solarized", "colors": {"ball": "#ffffff"}}`.
+/// PONG_THEME chooses the preset instead, and NO_COLOR forces the monochrome one
+///
+/// #Errors
+/// Returns an error if the theme file is not valid or names an unknown preset or color
+pub(crate) fn load_theme() -> Result<()> {
+    let config: serde_json::Value = match theme_path() {
+        Some(path) if path.exists() => {
+            let content = std::fs::read_to_string(&path)?;
+            serde_json::from_str(&content)
+                .map_err(|e| anyhow!("invalid theme file {}: {e}", path.display()))?
+        }
+        _ => serde_json::Value::Null,
+    };
+    let no_color = std::env::var("NO_COLOR").is_ok_and(|value| !value.is_empty());
+    let preset = match std::env::var("PONG_THEME") {
+        _ if no_color => "monochrome".to_string(),
+        Ok(preset) => preset,
+        _ => config["preset"].as_str().unwrap_or("classic").to_string(),
+    };
+    let mut theme = Theme::preset(&preset).ok_or_else(|| {
+        anyhow!("unknown theme {preset}, expected one of {}", PRESETS.join(", "))
+    })?;
+    if !no_color {
+        theme.apply_colors(&config["colors"])?;
+    }
+    let _ = THEME.set(theme);
+    Ok(())
+}
@@ -0,0 +1,354 @@
+use crate::Context;
+use crate::directory::get_users;
+use crate::pager::Pager;
+use anyhow::{Result, anyhow};
+use std::collections::HashMap;
+use std::rc::Rc;
+use std::time::{Duration, Instant};
+
+///Number of players a tournament can be created for
+pub(crate) const TOURNAMENT_SIZES: [u64; 3] = [4, 8, 16];
+///Largest size accepted from the server
+const MAX_TOURNAMENT_SIZE: u64 = TOURNAMENT_SIZES[TOURNAMENT_SIZES.len() - 1];
+///Minimum time between two fetches of the bracket
+const BRACKET_REFRESH: Duration = Duration::from_secs(2);
+///Time given to read the notification before a ready match starts
+pub(crate) const MATCH_COUNTDOWN: Duration = Duration::from_secs(3);
+
+#[derive(Clone, Copy, PartialEq, Default)]
+pub(crate) enum TournamentStatus {
+    #[default]
+    Waiting,
+    Ongoing,
+    Finished,
+}
+
+impl TournamentStatus {
+    fn from_value(value: &serde_json::Value) -> TournamentStatus {
+        match value.as_str() {
+            Some("ongoing") | Some("started") => TournamentStatus::Ongoing,
+            Some("finished") | Some("ended") => TournamentStatus::Finished,
+            _ => TournamentStatus::Waiting,
+        }
+    }
+    pub(crate) fn label(&self) -> &'static str {
+        match self {
+            TournamentStatus::Waiting => "waiting for players",
+            TournamentStatus::Ongoing => "ongoing",
+            TournamentStatus::Finished => "finished",
+        }
+    }
+}
+
+///A tournament as listed on the Tournaments screen
+pub(crate) struct TournamentSummary {
+    pub(crate) id: u64,
+    pub(crate) name: String,
+    pub(crate) size: u64,
+    pub(crate) players: Vec<u64>,
+    pub(crate) status: TournamentStatus,
+}
+
+impl TournamentSummary {
+    fn from_value(value: &serde_json::Value) -> Option<TournamentSummary> {
+        Some(TournamentSummary {
+            id: value["id"].as_u64()?,
+            name: value["name"].as_str()?.to_string(),
+            //Bounded so that the bracket can be laid out
+            size: value["size"].as_u64()?.min(MAX_TOURNAMENT_SIZE),
+            players: value["players"]
+                .as_array()
+                .map(|players| players.iter().filter_map(|id| id.as_u64()).collect())
+                .unwrap_or_default(),
+            status: TournamentStatus::from_value(&value["status"]),
+        })
+    }
+}
+
+///A match of the bracket, players being unknown until the previous round is over
+#[derive(Clone, Copy, PartialEq)]
+pub(crate) struct BracketMatch {
+    ///Round of the match, 0 being the first one
+    pub(crate) round: usize,
+    ///Position of the match in its round, from the top of the bracket
+    pub(crate) position: usize,
+    pub(crate) player1: Option<u64>,
+    pub(crate) player2: Option<u64>,
+    pub(crate) score1: u64,
+    pub(crate) score2: u64,
+    pub(crate) winner: Option<u64>,
+}
+
+impl BracketMatch {
+    fn from_value(value: &serde_json::Value) -> Option<BracketMatch> {
+        Some(BracketMatch {
+            round: value["round"].as_u64()? as usize,
+            position: value["position"].as_u64()? as usize,
+            player1: value["player1Id"].as_u64(),
+            player2: value["player2Id"].as_u64(),
+            score1: value["player1Score"].as_u64().unwrap_or_default(),
+            score2: value["player2Score"].as_u64().unwrap_or_default(),
+            winner: value["winnerId"].as_u64(),
+        })
+    }
+    ///Opponent of the given player, if the player is in the match
+    pub(crate) fn opponent_of(&self, id: u64) -> Option<Option<u64>> {
+        match (self.player1, self.player2) {
+            (Some(player1), player2) if player1 == id => Some(player2),
+            (player1, Some(player2)) if player2 == id => Some(player1),
+            _ => None,
+        }
+    }
+}
+
+///A joined tournament and its bracket
+#[derive(Default)]
+pub(crate) struct Tournament {
+    pub(crate) id: u64,
+    pub(crate) name: String,
+    pub(crate) size: u64,
+    pub(crate) status: TournamentStatus,
+    pub(crate) players: Vec<u64>,
+    pub(crate) matches: Vec<BracketMatch>,
+    names: HashMap<u64, String>,
+}
+
+impl Tournament {
+    ///Fetch a tournament and the names of its players
+    ///
+    /// #Errors
+    /// Returns an error if the server does not know the tournament
+    pub(crate) async fn fetch(context: Rc<Context>, id: u64) -> Result<Tournament> {
+        let apiloc = format!("https://{}/api/tournament/{}", context.location, id);
+        let response = context.client.get(apiloc).send().await?;
+        let response: serde_json::Value = response.json().await?;
+        let summary = match TournamentSummary::from_value(&response) {
+            Some(summary) => summary,
+            _ => match response["message"].as_str() {
+                Some(message) => return Err(anyhow!(message.to_string())),
+                _ => return Err(anyhow!("Tournament not found")),
+            },
+        };
+        let mut matches: Vec<BracketMatch> = response["matches"]
+            .as_array()
+            .map(|matches| matches.iter().filter_map(BracketMatch::from_value).collect())
+            .unwrap_or_default();
+        matches.sort_by_key(|bracket_match| (bracket_match.round, bracket_match.position));
+        let names = get_users(context, &summary.players)
+            .await
+            .into_iter()
+            .map(|(id, profile)| (id, profile.name))
+            .collect();
+        Ok(Tournament {
+            id: summary.id,
+            name: summary.name,
+            size: summary.size,
+            status: summary.status,
+            players: summary.players,
+            matches,
+            names,
+        })
+    }
+    ///Number of rounds needed to get a winner
+    pub(crate) fn rounds(&self) -> usize {
+        self.size.max(2).next_power_of_two().trailing_zeros() as usize
+    }
+    ///Match of the bracket at the given place, if already drawn by the server
+    pub(crate) fn get_match(&self, round: usize, position: usize) -> Option<&BracketMatch> {
+        self.matches.iter().find(|bracket_match| {
+            bracket_match.round == round && bracket_match.position == position
+        })
+    }
+    pub(crate) fn name(&self, id: u64) -> String {
+        match self.names.get(&id) {
+            Some(name) => name.clone(),
+            _ => format!("#{id}"),
+        }
+    }
+    ///Match the given player has to play now
+    pub(crate) fn pending_match(&self, id: u64) -> Option<&BracketMatch> {
+        if self.status != TournamentStatus::Ongoing {
+            return None;
+        }
+        self.matches.iter().find(|bracket_match| {
+            bracket_match.winner.is_none()
+                && matches!(bracket_match.opponent_of(id), Some(Some(_)))
+        })
+    }
+    ///Winner of the final, once played
+    pub(crate) fn champion(&self) -> Option<u64> {
+        self.get_match(self.rounds().saturating_sub(1), 0)
+            .and_then(|last| last.winner)
+    }
+}
+
+///Send a tournament request and return the server's answer
+///
+/// #Errors
+/// Returns the message sent by the server if the request is refused
+async fn send_tournament_request(
+    context: &Context,
+    endpoint: &str,
+    body: serde_json::Value,
+) -> Result<serde_json::Value> {
+    let url = format!("https://{}/api/tournament/{}", context.location, endpoint);
+    let response = context
+        .client
+        .post(url)
+        .header("content-type", "application/json")
+        .json(&body)
+        .send()
+        .await?;
+    let success = response.status().is_success();
+    let value: serde_json::Value = response.json().await.unwrap_or_default();
+    match success {
+        true => Ok(value),
+        false => match value["message"].as_str() {
+            Some(message) => Err(anyhow!(message.to_string())),
+            _ => Err(anyhow!("Tournament request failed")),
+        },
+    }
+}
+
+///Create a tournament, the creator joining it
+///
+/// #Return
+/// The id of the new tournament
+pub(crate) async fn create_tournament(
+    context: &Context,
+    user_id: u64,
+    name: &str,
+    size: u64,
+) -> Result<u64> {
+    let body = serde_json::json!({ "name": name, "size": size, "userId": user_id });
+    let response = send_tournament_request(context, "create", body).await?;
+    match response["id"].as_u64().or(response["tournamentId"].as_u64()) {
+        Some(id) => Ok(id),
+        _ => Err(anyhow!("No tournament id in response")),
+    }
+}
+
+pub(crate) async fn join_tournament(context: &Context, user_id: u64, id: u64) -> Result<()> {
+    let body = serde_json::json!({ "tournamentId": id, "userId": user_id });
+    send_tournament_request(context, "join", body).await?;
+    Ok(())
+}
+
+///Name and size typed in the tournament creation popup
+#[derive(Default)]
+pub(crate) struct TournamentForm {
+    pub(crate) name: String,
+    ///Index in TOURNAMENT_SIZES
+    pub(crate) size: usize,
+}
+
+impl TournamentForm {
+    pub(crate) fn size(&self) -> u64 {
+        TOURNAMENT_SIZES[self.size]
+    }
+}
+
+///State of the Tournaments screen
+#[derive(Default)]
+pub(crate) struct TournamentList {
+    pub(crate) tournaments: Vec<TournamentSummary>,
+    pub(crate) pager: Pager,
+    pub(crate) form: Option<TournamentForm>,
+}
+
+impl TournamentList {
+    ///Fetch the tournaments of the server
+    ///
+    /// #Errors
+    /// Returns an error if the server does not send a list of tournaments
+    pub(crate) async fn fetch(context: Rc<Context>) -> Result<TournamentList> {
+        let apiloc = format!("https://{}/api/tournament/list", context.location);
+        let response = context.client.get(apiloc).send().await?;
+        let response: serde_json::Value = response.json().await?;
+        let tournaments: Vec<TournamentSummary> = match response.as_array() {
+            Some(tournaments) => tournaments
+                .iter()
+                .filter_map(TournamentSummary::from_value)
+                .collect(),
+            _ => match response["message"].as_str() {
+                Some(message) => return Err(anyhow!(message.to_string())),
+                _ => return Err(anyhow!("Tournaments unavailable")),
+            },
+        };
+        let mut list = TournamentList {
+            tournaments,
+            ..Default::default()
+        };
+        list.pager.set_len(list.tournaments.len());
+        Ok(list)
+    }
+    pub(crate) fn selected_tournament(&self) -> Option<&TournamentSummary> {
+        self.tournaments.get(self.pager.selected)
+    }
+    ///Update tournament pages depending on the terminal's size
+    pub(crate) fn update_index(&mut self, terminal: &mut ratatui::DefaultTerminal) {
+        let page_height = terminal.get_frame().area().height.saturating_sub(3) as usize;
+        self.pager.update(self.tournaments.len(), page_height);
+    }
+}
+
+///State of the Bracket screen
+#[derive(Default)]
+pub(crate) struct Bracket {
+    pub(crate) tournament: Tournament,
+    fetched: Option<Instant>,
+    ///Match ready to be played and the time its notification was displayed
+    pub(crate) ready: Option<(BracketMatch, Instant)>,
+    ///Last match launched, ignored until the server records its winner
+    played: Option<BracketMatch>,
+    ///Set when a tournament match is launched, until the game is created
+    pub(crate) starting: bool,
+}
+
+impl Bracket {
+    pub(crate) async fn load(context: Rc<Context>, id: u64) -> Result<Bracket> {
+        Ok(Bracket {
+            tournament: Tournament::fetch(context, id).await?,
+            fetched: Some(Instant::now()),
+            ..Default::default()
+        })
+    }
+    ///Fetch the bracket again if the last fetch is too old
+    pub(crate) async fn refresh(&mut self, context: Rc<Context>) -> Result<()> {
+        if self
+            .fetched
+            .is_some_and(|fetched| fetched.elapsed() < BRACKET_REFRESH)
+        {
+            return Ok(());
+        }
+        self.tournament = Tournament::fetch(context, self.tournament.id).await?;
+        self.fetched = Some(Instant::now());
+        Ok(())
+    }
+    ///Look for a match of the player ready to be played, starting its notification
+    pub(crate) fn check_ready(&mut self, id: u64) {
+        if self.ready.is_some() {
+            return;
+        }
+        let played = self.played;
+        if let Some(pending) = self.tournament.pending_match(id)
+            && played.is_none_or(|played| {
+                (played.round, played.position) != (pending.round, pending.position)
+            })
+        {
+            self.ready = Some((*pending, Instant::now()));
+        }
+    }
+    ///Whether the notification of the ready match was displayed long enough
+    pub(crate) fn countdown_over(&self) -> bool {
+        self.ready
+            .is_some_and(|(_, notified)| notified.elapsed() >= MATCH_COUNTDOWN)
+    }
+    ///Launch the ready match
+    pub(crate) fn start_match(&mut self) {
+        if let Some((ready, _)) = self.ready.take() {
+            self.played = Some(ready);
+            self.starting = true;
+        }
+    }
+}
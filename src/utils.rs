@@ -1,7 +1,5 @@
-use crate::Context;
 use anyhow::{Result, anyhow};
 use crossterm::event::{Event, KeyCode, KeyModifiers};
-use std::rc::Rc;
 
 pub(crate) const LOGO: &str = r#"
   ██████╗  ██████╗ ███╗   ██╗ ██████╗ 
@@ -12,7 +10,10 @@ pub(crate) const LOGO: &str = r#"
   ╚═╝      ╚═════╝ ╚═╝  ╚═══╝ ╚═════╝ 
   "#;
 
-#[derive(Clone, Copy, PartialEq, Default)]
+///Lines taken by the logo
+pub(crate) const LOGO_HEIGHT: u16 = 8;
+
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
 pub(crate) enum CurrentScreen {
     #[default]
     FirstScreen,
@@ -27,7 +28,17 @@ pub(crate) enum CurrentScreen {
     EndGame,
     FriendsDisplay,
     AddFriend,
-    DeleteFriend,
+    FriendRequests,
+    Profile,
+    Leaderboard,
+    LocalHistory,
+    Replay,
+    LiveGames,
+    Tournaments,
+    Bracket,
+    LocalSetup,
+    LocalStandings,
+    LocalPlay,
     ErrorScreen,
 }
 
@@ -55,29 +66,141 @@ pub(crate) fn should_exit(event: &Event) -> Result<bool> {
     Ok(false)
 }
 
-pub(crate) async fn get_name_from_id(context: Rc<Context>, id: u64) -> Result<String> {
-    let apiloc = format!(
-        "https://{}/api/user/get_profile_id?user_id={}",
-        context.location, id
-    );
-    let response = context.client.get(apiloc).send().await?;
-    let response: serde_json::Value = response.json().await?;
-    if let Some(result) = response["name"].as_str() {
-        return Ok(result.to_string());
+///Seconds elapsed since the Unix epoch
+pub(crate) fn now_timestamp() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or_default()
+}
+
+///Format a Unix timestamp as "YYYY-MM-DD HH:MM" (UTC)
+pub(crate) fn format_timestamp(timestamp: u64) -> String {
+    let days = (timestamp / 86400) as i64;
+    let seconds = timestamp % 86400;
+    let (year, month, day) = civil_from_days(days);
+    format!(
+        "{:04}-{:02}-{:02} {:02}:{:02}",
+        year,
+        month,
+        day,
+        seconds / 3600,
+        seconds % 3600 / 60
+    )
+}
+
+///Parse a "YYYY-MM-DD" date into the Unix timestamp of its first second (UTC)
+///
+/// #Errors
+/// Returns an error if the date is malformed, does not exist or is before 1970
+pub(crate) fn parse_date(date: &str) -> Result<u64> {
+    let mut parts = date.splitn(3, '-').map(|part| part.parse::<i64>());
+    match (parts.next(), parts.next(), parts.next()) {
+        (Some(Ok(year)), Some(Ok(month)), Some(Ok(day)))
+            if (1..=12).contains(&month) && (1..=31).contains(&day) =>
+        {
+            let days = days_from_civil(year, month, day);
+            //Days past the end of the month would be counted in the next one
+            if civil_from_days(days) != (year, month, day) {
+                return Err(anyhow!("invalid date {date}, no such day"));
+            }
+            Ok(u64::try_from(days * 86400)?)
+        }
+        _ => Err(anyhow!("invalid date {date}, expected YYYY-MM-DD")),
     }
-    Err(anyhow!("Opponent as no name"))
 }
 
-pub(crate) async fn get_id_from_name(context: Rc<Context>, name: &String) -> Result<i64> {
-    let apiloc = format!(
-        "https://{}/api/user/get_profile_name?profile_name={}",
-        context.location, name
-    );
-    let response = context.client.get(apiloc).send().await?;
-    let response: serde_json::Value = response.json().await?;
-    let result: i64 = match response["id"].as_i64() {
-        Some(id) => id,
-        _ => return Err(anyhow!("Friend not found")),
-    };
-    Ok(result)
-}
\ No newline at end of file
+///Days since 1970-01-01 of a proleptic Gregorian date
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * (month + if month > 2 { -3 } else { 9 }) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146097 + day_of_era - 719468
+}
+
+///Proleptic Gregorian date of a number of days since 1970-01-01
+fn civil_from_days(days: i64) -> (i64, i64, i64) {
+    let days = days + 719468;
+    let era = days.div_euclid(146097);
+    let day_of_era = days - era * 146097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 { month_index + 3 } else { month_index - 9 };
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+    (year, month, day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn civil_dates_and_days() {
+        for (date, days) in [
+            ((1970, 1, 1), 0),
+            ((1969, 12, 31), -1),
+            ((2000, 2, 29), 11016),
+            ((2000, 3, 1), 11017),
+            ((2023, 12, 31), 19722),
+            ((2024, 1, 1), 19723),
+            ((2024, 2, 29), 19782),
+            ((2100, 3, 1), 47541),
+        ] {
+            assert_eq!(days_from_civil(date.0, date.1, date.2), days);
+            assert_eq!(civil_from_days(days), date);
+        }
+    }
+
+    #[test]
+    fn month_ends() {
+        for (year, month, last) in [
+            (2023, 1, 31),
+            (2023, 2, 28),
+            (2024, 2, 29),
+            (1900, 2, 28),
+            (2000, 2, 29),
+            (2023, 4, 30),
+            (2023, 12, 31),
+        ] {
+            let days = days_from_civil(year, month, last);
+            assert_eq!(civil_from_days(days), (year, month, last));
+            let next = match month {
+                12 => (year + 1, 1, 1),
+                _ => (year, month + 1, 1),
+            };
+            assert_eq!(civil_from_days(days + 1), next);
+        }
+    }
+
+    #[test]
+    fn parse_valid_dates() {
+        assert_eq!(parse_date("1970-01-01").unwrap(), 0);
+        assert_eq!(parse_date("2024-02-29").unwrap(), 1709164800);
+        assert_eq!(parse_date("2024-3-1").unwrap(), 1709251200);
+    }
+
+    #[test]
+    fn parse_invalid_dates() {
+        for date in [
+            "",
+            "2024",
+            "2024-02",
+            "2024-13-01",
+            "2024-00-10",
+            "2024-01-32",
+            "2023-02-29",
+            "1900-02-29",
+            "2024-04-31",
+            "1969-12-31",
+            "2024-01-01T10:00",
+            "yesterday",
+        ] {
+            assert!(parse_date(date).is_err(), "{date} was accepted");
+        }
+    }
+}